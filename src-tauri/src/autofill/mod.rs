@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params_from_iter, types::Value};
+
+pub mod types;
+
+use crate::{
+    browsers::{ensure_browser_closed, resolve_profile, ProfileLocation},
+    database::{DeletionReport, WorkingCopy},
+};
+use types::{AutofillEntry, AutofillFilter};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+pub fn web_data_path(location: &ProfileLocation) -> Result<PathBuf, String> {
+    let web_data = location.profile_path.join("Web Data");
+    if !web_data.exists() {
+        return Err(format!("Web Data file does not exist: {:?}", web_data));
+    }
+    Ok(web_data)
+}
+
+impl AutofillFilter {
+    /// `autofill` dates are Unix seconds, unlike the WebKit timestamps of
+    /// `Login Data`.
+    fn where_clause(&self) -> Result<(String, Vec<Value>), String> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if !self.names.is_empty() {
+            conditions.push(format!("name IN ({})", vec!["?"; self.names.len()].join(", ")));
+            params.extend(self.names.iter().map(|name| Value::Text(name.clone())));
+        }
+        if let Some(value) = &self.value {
+            conditions.push("value_lower = ?".to_string());
+            params.push(Value::Text(value.to_lowercase()));
+        }
+        if let Some(days) = self.unused_days {
+            conditions.push("date_last_used < ?".to_string());
+            params.push(Value::Integer(Utc::now().timestamp() - days * SECONDS_PER_DAY));
+        }
+
+        if conditions.is_empty() {
+            return Err("Autofill filter has no conditions".to_string());
+        }
+        Ok((conditions.join(" AND "), params))
+    }
+}
+
+#[tauri::command]
+pub fn autofill_entries(browser_name: String, profile_name: String) -> Result<Vec<AutofillEntry>, String> {
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    let working_copy = WorkingCopy::new(&web_data_path(&location)?)?;
+    let conn = working_copy.open()?;
+
+    let mut stmt = conn
+        .prepare("SELECT name, length(value), count, date_created, date_last_used FROM autofill ORDER BY name, date_last_used DESC")
+        .map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(AutofillEntry {
+                name: row.get(0)?,
+                value_length: row.get(1)?,
+                count: row.get(2)?,
+                date_created: DateTime::from_timestamp(row.get(3)?, 0).unwrap_or_default(),
+                date_last_used: DateTime::from_timestamp(row.get(4)?, 0).unwrap_or_default(),
+            })
+        })
+        .map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+
+    entries.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read autofill entries: {}", e))
+}
+
+#[tauri::command]
+pub fn delete_autofill_entries(browser_name: String, profile_name: String, filter: AutofillFilter) -> Result<DeletionReport, String> {
+    let (clause, params) = filter.where_clause()?;
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    let web_data = web_data_path(&location)?;
+    ensure_browser_closed(&location.browser)?;
+
+    let working_copy = WorkingCopy::new(&web_data)?;
+    let rows_affected = {
+        let conn = working_copy.open()?;
+        conn.execute(&format!("DELETE FROM autofill WHERE {}", clause), params_from_iter(params))
+            .map_err(|e| format!("Failed to delete autofill entries: {}", e))?
+    }; // conn is dropped here, closing the file handle
+    println!("Successfully deleted {} autofill entries from profile: {}", rows_affected, location.profile);
+
+    Ok(DeletionReport {
+        rows_deleted: rows_affected,
+        secure_deletion: working_copy.write_back_deleted(&web_data)?,
+    })
+}
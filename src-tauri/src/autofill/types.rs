@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An autocomplete value saved for a form field. Only the length of the
+/// value is exposed.
+#[derive(Serialize)]
+pub struct AutofillEntry {
+    pub name: String,
+    pub value_length: i64,
+    pub count: i64,
+    pub date_created: DateTime<Utc>,
+    pub date_last_used: DateTime<Utc>,
+}
+
+/// Selects autocomplete values to delete. Every condition that is set must
+/// match; at least one is required.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AutofillFilter {
+    /// Form field names, e.g. `email` or `username`.
+    pub names: Vec<String>,
+    /// Value to match, ignoring case.
+    pub value: Option<String>,
+    /// Not used for at least this many days.
+    pub unused_days: Option<i64>,
+}
@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{logins::EntryRef, passwords::types::Browsers};
+
+/// A resolved browser profile, the directory holding its data files and the
+/// location of its `Login Data` database.
+pub struct ProfileLocation {
+    pub browser: String,
+    pub profile: String,
+    pub profile_path: PathBuf,
+    pub login_data: PathBuf,
+}
+
+pub fn chromium_browsers() -> Result<Vec<Browsers>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let local_appdata = env::var("LOCALAPPDATA").map_err(|e| format!("Failed to get LOCALAPPDATA: {}", e))?;
+        let roaming_appdata = env::var("APPDATA").map_err(|e| format!("Failed to get APPDATA: {}", e))?;
+
+        Ok(vec![
+            Browsers {
+                name: "Brave Browser".to_string(),
+                path: "BraveSoftware\\Brave-Browser\\User Data".to_string(),
+                root: local_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Chromium".to_string(),
+                path: "Chromium\\User Data".to_string(),
+                root: local_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Google Chrome".to_string(),
+                path: "Google\\Chrome\\User Data".to_string(),
+                root: local_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Microsoft Edge".to_string(),
+                path: "Microsoft\\Edge\\User Data".to_string(),
+                root: local_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Opera".to_string(),
+                path: "Opera Software\\Opera Stable".to_string(),
+                root: roaming_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Opera GX".to_string(),
+                path: "Opera Software\\Opera GX Stable".to_string(),
+                root: roaming_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Vivaldi".to_string(),
+                path: "Vivaldi\\User Data".to_string(),
+                root: local_appdata.to_owned(),
+            },
+        ])
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let home = env::var("HOME").map_err(|e| format!("Failed to get HOME: {}", e))?;
+
+        Ok(vec![
+            Browsers {
+                name: "Brave Browser".to_string(),
+                path: ".config/BraveSoftware/Brave-Browser".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Chromium".to_string(),
+                path: ".config/chromium".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Google Chrome".to_string(),
+                path: ".config/google-chrome".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Microsoft Edge".to_string(),
+                path: ".config/microsoft-edge".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Opera".to_string(),
+                path: ".config/opera".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Vivaldi".to_string(),
+                path: ".config/vivaldi".to_string(),
+                root: home.to_owned(),
+            },
+        ])
+    }
+}
+
+pub fn find_browser(browser_name: &str) -> Result<Browsers, String> {
+    chromium_browsers()?
+        .into_iter()
+        .find(|b| b.name == browser_name)
+        .ok_or_else(|| format!("Browser not found: {}", browser_name))
+}
+
+pub fn browser_path(browser: &Browsers) -> PathBuf {
+    Path::new(&browser.root).join(&browser.path)
+}
+
+pub fn read_local_state(browser_path: &Path) -> Result<serde_json::Value, String> {
+    let local_state_path = browser_path.join("Local State");
+    if !local_state_path.exists() {
+        return Err(format!("Local State file does not exist: {:?}", local_state_path));
+    }
+
+    let local_state_file = fs::File::open(&local_state_path).map_err(|e| format!("Failed to open Local State file: {}", e))?;
+    serde_json::from_reader(local_state_file).map_err(|e| format!("Failed to parse Local State JSON: {}", e))
+}
+
+/// Lists `(directory name, display name)` pairs from `profile.info_cache`.
+pub fn profile_names(local_state: &serde_json::Value) -> Vec<(String, String)> {
+    let mut profiles = Vec::new();
+    if let Some(info_cache) = local_state["profile"]["info_cache"].as_object() {
+        for (dir_name, profile_info) in info_cache {
+            if let Some(name) = profile_info["name"].as_str() {
+                profiles.push((dir_name.clone(), name.to_string()));
+            }
+        }
+    }
+    profiles
+}
+
+/// Opera GX keeps the data of all its profiles directly in the user data
+/// directory.
+pub fn profile_path(browser: &Browsers, browser_path: &Path, profile_dir: &str) -> PathBuf {
+    if browser.name == "Opera GX" {
+        browser_path.to_path_buf()
+    } else {
+        browser_path.join(profile_dir)
+    }
+}
+
+/// Chromium holds a lock in its user data directory while it runs: `lockfile`,
+/// opened without sharing, on Windows and the `SingletonLock` symlink to
+/// `<hostname>-<pid>` elsewhere.
+#[cfg(target_os = "windows")]
+fn is_running(browser_path: &Path) -> bool {
+    let lockfile = browser_path.join("lockfile");
+    lockfile.exists() && fs::OpenOptions::new().write(true).open(&lockfile).is_err()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_running(browser_path: &Path) -> bool {
+    let Ok(target) = fs::read_link(browser_path.join("SingletonLock")) else {
+        return false;
+    };
+
+    if cfg!(target_os = "linux") {
+        let target = target.to_string_lossy();
+        match target.rsplit('-').next().and_then(|pid| pid.parse::<u32>().ok()) {
+            Some(pid) => Path::new("/proc").join(pid.to_string()).exists(),
+            None => true,
+        }
+    } else {
+        true
+    }
+}
+
+/// Fails when the browser is running, since it would overwrite or lose
+/// changes made to its databases underneath it.
+pub fn ensure_browser_closed(browser_name: &str) -> Result<(), String> {
+    let browser = find_browser(browser_name)?;
+    if is_running(&browser_path(&browser)) {
+        return Err(format!("{} is running; close it before modifying its data", browser_name));
+    }
+    Ok(())
+}
+
+/// Resolves a profile by its display name, as shown in the scan output.
+pub fn resolve_profile(browser_name: &str, profile_name: &str) -> Result<ProfileLocation, String> {
+    let browser = find_browser(browser_name)?;
+    let browser_path = browser_path(&browser);
+    if !browser_path.exists() {
+        return Err(format!("Browser path does not exist: {:?}", browser_path));
+    }
+
+    let cleaned_profile_name = profile_name.trim_matches('"').to_string();
+    let local_state_json = read_local_state(&browser_path)?;
+
+    let profile_dir = profile_names(&local_state_json)
+        .into_iter()
+        .find(|(_, name)| *name == cleaned_profile_name)
+        .map(|(dir_name, _)| dir_name)
+        .ok_or_else(|| format!("Could not find actual directory name for profile: {}", cleaned_profile_name))?;
+
+    let profile_path = profile_path(&browser, &browser_path, &profile_dir);
+    if !profile_path.exists() {
+        return Err(format!("Profile directory does not exist: {:?}", profile_path));
+    }
+
+    Ok(ProfileLocation {
+        browser: browser.name,
+        profile: cleaned_profile_name,
+        login_data: profile_path.join("Login Data"),
+        profile_path,
+    })
+}
+
+/// Like [`resolve_profile`], for operations on the profile's `Login Data`.
+pub fn locate_profile(browser_name: &str, profile_name: &str) -> Result<ProfileLocation, String> {
+    let location = resolve_profile(browser_name, profile_name)?;
+    if !location.login_data.exists() {
+        return Err(format!("Login Data file does not exist: {:?}", location.login_data));
+    }
+    Ok(location)
+}
+
+/// Every profile directory of every installed browser, whether or not it has
+/// saved logins. Browsers whose `Local State` cannot be read are skipped.
+pub fn installed_profiles() -> Result<Vec<ProfileLocation>, String> {
+    let mut locations = Vec::new();
+    for browser in chromium_browsers()? {
+        let browser_path = browser_path(&browser);
+        if !browser_path.exists() {
+            continue;
+        }
+
+        let local_state_json = match read_local_state(&browser_path) {
+            Ok(json) => json,
+            Err(_) => continue,
+        };
+
+        for (profile_dir, profile_name) in profile_names(&local_state_json) {
+            let profile_path = profile_path(&browser, &browser_path, &profile_dir);
+            // Opera GX keeps the data of all of its profiles in one directory.
+            if !profile_path.exists() || locations.iter().any(|l: &ProfileLocation| l.profile_path == profile_path) {
+                continue;
+            }
+            locations.push(ProfileLocation {
+                browser: browser.name.clone(),
+                profile: profile_name,
+                login_data: profile_path.join("Login Data"),
+                profile_path,
+            });
+        }
+    }
+    Ok(locations)
+}
+
+/// Every profile of every installed browser that has a `Login Data` database.
+pub fn all_profiles() -> Result<Vec<ProfileLocation>, String> {
+    Ok(installed_profiles()?.into_iter().filter(|l| l.login_data.exists()).collect())
+}
+
+/// Entries of a batch that live in the same `Login Data` database.
+pub struct LoginDataGroup {
+    pub browser: String,
+    pub login_data: PathBuf,
+    pub indices: Vec<usize>,
+}
+
+/// Groups batch entries by database, resolving each browser profile once.
+/// Entries whose profile cannot be resolved are returned with the error.
+pub fn group_by_login_data(entries: &[EntryRef]) -> (Vec<LoginDataGroup>, Vec<(usize, String)>) {
+    let mut locations: HashMap<(String, String), Result<(String, PathBuf), String>> = HashMap::new();
+    let mut groups: Vec<LoginDataGroup> = Vec::new();
+    let mut failures = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let location = locations
+            .entry((entry.browser.clone(), entry.profile.clone()))
+            .or_insert_with(|| locate_profile(&entry.browser, &entry.profile).map(|location| (location.browser, location.login_data)))
+            .clone();
+
+        match location {
+            Ok((browser, login_data)) => match groups.iter_mut().find(|group| group.login_data == login_data) {
+                Some(group) => group.indices.push(index),
+                None => groups.push(LoginDataGroup {
+                    browser,
+                    login_data,
+                    indices: vec![index],
+                }),
+            },
+            Err(e) => failures.push((index, e)),
+        }
+    }
+
+    (groups, failures)
+}
@@ -0,0 +1,216 @@
+use chrono::Utc;
+use regex::{Regex, RegexBuilder};
+use tauri::State;
+use url::Url;
+
+pub mod types;
+
+use crate::{
+    browsers::all_profiles,
+    logins::{LoginFilter, LoginRow},
+    passwords::utils::unix_to_webkit_time,
+    plan::{
+        issue_plan, plan_target,
+        types::{DeletionPlan, PlanKind, PlanState, SkippedProfile},
+    },
+};
+use types::CleanupRule;
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+/// A [`CleanupRule`] with its patterns compiled and its ages turned into
+/// WebKit timestamps, fixed at the time the rule was compiled.
+#[derive(Clone)]
+pub struct CompiledRule {
+    domain_glob: Option<Regex>,
+    domain_regex: Option<Regex>,
+    username: Option<Regex>,
+    modified_before: Option<i64>,
+    used_before: Option<i64>,
+    max_times_used: Option<i64>,
+}
+
+impl CompiledRule {
+    pub fn compile(rule: &CleanupRule) -> Result<Self, String> {
+        let now = Utc::now().timestamp();
+        let cutoff = |days: i64| unix_to_webkit_time(now - days * SECONDS_PER_DAY);
+
+        let compiled = CompiledRule {
+            domain_glob: rule.domain_glob.as_deref().map(glob_to_regex).transpose()?,
+            domain_regex: rule.domain_regex.as_deref().map(|r| build_regex(r, true)).transpose()?,
+            username: rule.username_pattern.as_deref().map(|r| build_regex(r, false)).transpose()?,
+            modified_before: rule.modified_days_ago.map(cutoff),
+            used_before: rule.unused_days.map(cutoff),
+            max_times_used: rule.max_times_used,
+        };
+
+        let has_condition = compiled.domain_glob.is_some()
+            || compiled.domain_regex.is_some()
+            || compiled.username.is_some()
+            || compiled.modified_before.is_some()
+            || compiled.used_before.is_some()
+            || compiled.max_times_used.is_some();
+        if !has_condition {
+            return Err("Cleanup rule has no conditions; use delete_all_passwords to remove every login".to_string());
+        }
+
+        Ok(compiled)
+    }
+
+    /// The optional `logins` columns this rule reads.
+    pub fn required_columns(&self) -> Vec<&'static str> {
+        let mut columns = Vec::new();
+        if self.modified_before.is_some() {
+            columns.push("date_password_modified");
+        }
+        if self.used_before.is_some() {
+            columns.push("date_last_used");
+        }
+        if self.max_times_used.is_some() {
+            columns.push("times_used");
+        }
+        columns
+    }
+
+    pub fn matches(&self, row: &LoginRow) -> bool {
+        let host = login_host(row);
+
+        self.domain_glob.as_ref().is_none_or(|r| r.is_match(&host))
+            && self.domain_regex.as_ref().is_none_or(|r| r.is_match(&host))
+            && self.username.as_ref().is_none_or(|r| r.is_match(&row.username))
+            && self.modified_before.is_none_or(|cutoff| or_created(row.date_password_modified, row) < cutoff)
+            && self.used_before.is_none_or(|cutoff| or_created(row.date_last_used, row) < cutoff)
+            && self.max_times_used.is_none_or(|max| row.times_used <= max)
+    }
+}
+
+fn or_created(timestamp: i64, row: &LoginRow) -> i64 {
+    if timestamp == 0 {
+        row.date_created
+    } else {
+        timestamp
+    }
+}
+
+fn login_host(row: &LoginRow) -> String {
+    Url::parse(&row.url)
+        .or_else(|_| Url::parse(&row.origin_url))
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| row.url.clone())
+}
+
+fn build_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+}
+
+fn glob_to_regex(glob: &str) -> Result<Regex, String> {
+    let (apex, rest) = match glob.strip_prefix("*.") {
+        Some(rest) => ("(?:.*\\.)?", rest),
+        None => ("", glob),
+    };
+    let body = regex::escape(rest).replace("\\*", ".*").replace("\\?", ".");
+    build_regex(&format!("^{}{}$", apex, body), true)
+}
+
+/// Previews a cleanup rule across all browsers. The returned plan lists every
+/// matching login and is applied with `execute_plan`; profiles the rule
+/// could not be evaluated on are listed as skipped.
+#[tauri::command]
+pub fn plan_cleanup(rule: CleanupRule, state: State<PlanState>) -> Result<DeletionPlan, String> {
+    let compiled = CompiledRule::compile(&rule)?;
+
+    let mut targets = Vec::new();
+    let mut skipped = Vec::new();
+    for location in all_profiles()? {
+        if !rule.in_scope(&location.browser, &location.profile) {
+            continue;
+        }
+        match plan_target(&location, LoginFilter::Rule(compiled.clone())) {
+            Ok(target) => targets.push(target),
+            Err(error) => skipped.push(SkippedProfile {
+                browser: location.browser,
+                profile: location.profile,
+                error,
+            }),
+        }
+    }
+
+    Ok(issue_plan(PlanKind::Cleanup { rule }, targets, skipped, &state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(url: &str, username: &str) -> LoginRow {
+        LoginRow {
+            id: 1,
+            url: url.to_string(),
+            origin_url: url.to_string(),
+            username: username.to_string(),
+            date_created: unix_to_webkit_time(Utc::now().timestamp()),
+            date_password_modified: 0,
+            date_last_used: 0,
+            times_used: 0,
+        }
+    }
+
+    #[test]
+    fn glob_matches_subdomains_and_apex() {
+        let regex = glob_to_regex("*.oldcorp.com").unwrap();
+        assert!(regex.is_match("oldcorp.com"));
+        assert!(regex.is_match("mail.OldCorp.com"));
+        assert!(!regex.is_match("notoldcorp.com"));
+        assert!(!regex.is_match("oldcorp.com.evil.net"));
+    }
+
+    #[test]
+    fn glob_escapes_regex_characters() {
+        let regex = glob_to_regex("a?c.example.com").unwrap();
+        assert!(regex.is_match("abc.example.com"));
+        assert!(!regex.is_match("abc-example.com"));
+    }
+
+    #[test]
+    fn rule_without_conditions_is_rejected() {
+        assert!(CompiledRule::compile(&CleanupRule::default()).is_err());
+    }
+
+    #[test]
+    fn rule_requires_every_condition() {
+        let rule = CleanupRule {
+            domain_glob: Some("*.oldcorp.com".to_string()),
+            username_pattern: Some("^$".to_string()),
+            ..Default::default()
+        };
+        let compiled = CompiledRule::compile(&rule).unwrap();
+
+        assert!(compiled.matches(&row("https://login.oldcorp.com/", "")));
+        assert!(!compiled.matches(&row("https://login.oldcorp.com/", "alice")));
+        assert!(!compiled.matches(&row("https://example.com/", "")));
+        assert!(compiled.required_columns().is_empty());
+    }
+
+    #[test]
+    fn unused_logins_fall_back_to_creation_date() {
+        let rule = CleanupRule {
+            unused_days: Some(30),
+            ..Default::default()
+        };
+        let compiled = CompiledRule::compile(&rule).unwrap();
+
+        let recent = row("https://example.com/", "alice");
+        assert!(!compiled.matches(&recent));
+
+        let old = LoginRow {
+            date_created: unix_to_webkit_time(Utc::now().timestamp() - 60 * SECONDS_PER_DAY),
+            ..row("https://example.com/", "alice")
+        };
+        assert!(compiled.matches(&old));
+        assert_eq!(compiled.required_columns(), vec!["date_last_used"]);
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Conditions selecting saved logins for bulk deletion. Every condition that
+/// is set must match; empty `browsers`/`profiles` mean all of them.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CleanupRule {
+    /// Host glob such as `*.oldcorp.com`. A leading `*.` also matches the
+    /// bare domain.
+    pub domain_glob: Option<String>,
+    /// Regular expression matched against the host.
+    pub domain_regex: Option<String>,
+    /// Regular expression matched against the username; `^$` selects logins
+    /// saved without one.
+    pub username_pattern: Option<String>,
+    /// Password not changed for at least this many days.
+    pub modified_days_ago: Option<i64>,
+    /// Login not used for at least this many days. Logins that were never
+    /// used count from their creation date.
+    pub unused_days: Option<i64>,
+    /// Login used at most this many times.
+    pub max_times_used: Option<i64>,
+    pub browsers: Vec<String>,
+    pub profiles: Vec<String>,
+}
+
+impl CleanupRule {
+    pub fn in_scope(&self, browser: &str, profile: &str) -> bool {
+        (self.browsers.is_empty() || self.browsers.iter().any(|b| b == browser)) && (self.profiles.is_empty() || self.profiles.iter().any(|p| p == profile))
+    }
+}
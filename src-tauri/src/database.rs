@@ -0,0 +1,301 @@
+use std::{
+    fs::{self, File, FileTimes, Metadata},
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tempfile::{tempdir, Builder, NamedTempFile, TempDir};
+
+/// Sidecar files SQLite keeps next to a database. A hot `-journal` or a
+/// `-wal` holds changes that belong to the main file, so they are copied
+/// with it; `-shm` is only an index and is rebuilt.
+const JOURNAL_SUFFIXES: [&str; 3] = ["-journal", "-wal", "-shm"];
+
+/// How a write-back made deleted rows unrecoverable.
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct SecureDeletion {
+    /// The database was rebuilt with `VACUUM` and `secure_delete` on, so
+    /// deleted content is gone from the file. `false` for write-backs that
+    /// deleted nothing.
+    pub applied: bool,
+    /// Stale journal files removed next to the replaced database.
+    pub journals_removed: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct DeletionReport {
+    pub rows_deleted: usize,
+    pub secure_deletion: SecureDeletion,
+}
+
+fn sidecar_path(database: &Path, suffix: &str) -> PathBuf {
+    let mut path = database.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// A private copy of a browser database. The browser keeps the live file
+/// locked while it runs, so reads and edits go through a copy that is
+/// written back once the changes are complete.
+pub struct WorkingCopy {
+    _dir: TempDir,
+    path: PathBuf,
+}
+
+impl WorkingCopy {
+    pub fn new(source: &Path) -> Result<Self, String> {
+        let dir = tempdir().map_err(|e| format!("Failed to create temporary directory: {}", e))?;
+        let file_name = source.file_name().ok_or_else(|| format!("Invalid database path: {:?}", source))?;
+        let path = dir.path().join(file_name);
+
+        fs::copy(source, &path).map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        for suffix in &JOURNAL_SUFFIXES[..2] {
+            let sidecar = sidecar_path(source, suffix);
+            if sidecar.exists() {
+                fs::copy(&sidecar, sidecar_path(&path, suffix)).map_err(|e| format!("Failed to copy {}: {}", sidecar.display(), e))?;
+            }
+        }
+
+        Ok(WorkingCopy { _dir: dir, path })
+    }
+
+    pub fn open(&self) -> Result<Connection, String> {
+        Connection::open(&self.path).map_err(|e| format!("Failed to open SQLite connection: {}", e))
+    }
+
+    /// Folds any WAL content into the main file so the file written back is
+    /// self-contained. With `vacuum`, also rebuilds it with `secure_delete` on
+    /// so deleted content neither lingers in free pages nor inside live ones;
+    /// returns whether that happened.
+    fn compact(&self, vacuum: bool) -> Result<bool, String> {
+        let conn = self.open()?;
+        conn.execute_batch("PRAGMA journal_mode = DELETE;").map_err(|e| format!("Failed to checkpoint modified database: {}", e))?;
+        if !vacuum {
+            return Ok(false);
+        }
+
+        let secure_delete: bool = conn
+            .query_row("PRAGMA secure_delete = ON", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to enable secure_delete: {}", e))?;
+        conn.execute_batch("VACUUM;").map_err(|e| format!("Failed to vacuum modified database: {}", e))?;
+        Ok(secure_delete)
+    }
+
+    pub fn integrity_check(&self) -> Result<(), String> {
+        let conn = self.open()?;
+        let result: String = conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to run integrity check: {}", e))?;
+
+        if result == "ok" {
+            Ok(())
+        } else {
+            Err(format!("Integrity check failed for modified {}: {}", self.path.display(), result))
+        }
+    }
+
+    /// Checks the working copy's integrity and replaces `target` with it.
+    /// Every connection to the copy must be closed before calling this.
+    pub fn write_back(&self, target: &Path) -> Result<(), String> {
+        self.replace(target, false).map(|_| ())
+    }
+
+    /// Like [`write_back`](Self::write_back), after vacuuming the copy so the
+    /// deleted rows cannot be recovered from the file written back.
+    pub fn write_back_deleted(&self, target: &Path) -> Result<SecureDeletion, String> {
+        self.replace(target, true)
+    }
+
+    /// Journals left next to `target` describe the old file and would corrupt
+    /// or resurrect data in the new one, so they are moved aside before the
+    /// swap, restored if it fails and removed once it succeeded.
+    fn replace(&self, target: &Path, vacuum: bool) -> Result<SecureDeletion, String> {
+        let applied = self.compact(vacuum)?;
+        self.integrity_check()?;
+
+        let mut set_aside = Vec::new();
+        for suffix in JOURNAL_SUFFIXES {
+            let sidecar = sidecar_path(target, suffix);
+            if !sidecar.exists() {
+                continue;
+            }
+            let moved = sidecar_path(target, &format!("{}.totlaunloc-old", suffix));
+            if let Err(e) = fs::rename(&sidecar, &moved) {
+                restore_sidecars(&set_aside);
+                return Err(format!("Failed to move stale journal {} aside: {}", sidecar.display(), e));
+            }
+            set_aside.push((sidecar, moved));
+        }
+
+        let replaced = replace_file(target, |staged| {
+            let mut source = File::open(&self.path)?;
+            io::copy(&mut source, staged).map(|_| ())
+        });
+        if let Err(e) = replaced {
+            restore_sidecars(&set_aside);
+            return Err(e);
+        }
+
+        let mut journals_removed = Vec::new();
+        for (sidecar, moved) in set_aside {
+            if let Err(e) = fs::remove_file(&moved) {
+                eprintln!("Failed to remove stale journal {}: {}", moved.display(), e);
+            }
+            journals_removed.push(sidecar.display().to_string());
+        }
+
+        println!("Successfully wrote modified database back to: {}", target.display());
+        Ok(SecureDeletion { applied, journals_removed })
+    }
+}
+
+fn restore_sidecars(set_aside: &[(PathBuf, PathBuf)]) {
+    for (sidecar, moved) in set_aside {
+        if let Err(e) = fs::rename(moved, sidecar) {
+            eprintln!("Failed to restore journal {}: {}", sidecar.display(), e);
+        }
+    }
+}
+
+/// Atomically replaces (or creates) `target` with the contents produced by
+/// `write`.
+///
+/// The new contents are staged next to `target` with the original's
+/// permissions, ownership and timestamps, synced and then renamed over it.
+/// If any step before the rename fails the staged file is removed and
+/// `target` is left untouched.
+pub fn replace_file(target: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> Result<(), String> {
+    let dir = target.parent().ok_or_else(|| format!("Invalid path: {:?}", target))?;
+    let metadata = match fs::metadata(target) {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read metadata of {}: {}", target.display(), e)),
+    };
+
+    let mut staged = stage(dir, metadata.as_ref(), write).map_err(|e| format!("Failed to stage replacement for {}: {}", target.display(), e))?;
+
+    let mut attempts = 0;
+    const MAX_ATTEMPTS: u8 = 5;
+    const RETRY_DELAY_MS: u64 = 100;
+
+    loop {
+        match staged.persist(target) {
+            Ok(_) => break,
+            Err(e) => {
+                attempts += 1;
+                if attempts >= MAX_ATTEMPTS {
+                    return Err(format!("Failed to replace {} after {} attempts: {}", target.display(), MAX_ATTEMPTS, e.error));
+                }
+                println!("Retrying replacement (attempt {}/{}): {}", attempts, MAX_ATTEMPTS, target.display());
+                staged = e.file;
+                thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
+            }
+        }
+    }
+
+    if let Err(e) = sync_dir(dir) {
+        eprintln!("Failed to sync directory {}: {}", dir.display(), e);
+    }
+    Ok(())
+}
+
+fn stage(dir: &Path, metadata: Option<&Metadata>, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<NamedTempFile> {
+    let mut staged = Builder::new().prefix(".totlaunloc-").suffix(".tmp").tempfile_in(dir)?;
+    write(staged.as_file_mut())?;
+
+    if let Some(metadata) = metadata {
+        copy_ownership(staged.as_file(), metadata)?;
+        fs::set_permissions(staged.path(), metadata.permissions())?;
+        copy_times(staged.as_file(), metadata)?;
+    }
+    staged.as_file().sync_all()?;
+
+    Ok(staged)
+}
+
+fn copy_times(file: &File, metadata: &Metadata) -> io::Result<()> {
+    let times = FileTimes::new().set_accessed(metadata.accessed()?).set_modified(metadata.modified()?);
+
+    #[cfg(target_os = "windows")]
+    let times = {
+        use std::os::windows::fs::FileTimesExt;
+        times.set_created(metadata.created()?)
+    };
+
+    file.set_times(times)
+}
+
+#[cfg(unix)]
+fn copy_ownership(file: &File, metadata: &Metadata) -> io::Result<()> {
+    use std::os::unix::fs::{fchown, MetadataExt};
+    fchown(file, Some(metadata.uid()), Some(metadata.gid()))
+}
+
+// Windows files inherit their ACL from the directory they are created in.
+#[cfg(not(unix))]
+fn copy_ownership(_file: &File, _metadata: &Metadata) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_database(dir: &Path) -> PathBuf {
+        let live = dir.join("Web Data");
+        let conn = Connection::open(&live).unwrap();
+        conn.execute_batch("CREATE TABLE t (v TEXT);").unwrap();
+        for i in 0..2000 {
+            conn.execute("INSERT INTO t VALUES (?1)", [format!("secretvalue{}", i)]).unwrap();
+        }
+        live
+    }
+
+    #[test]
+    fn write_back_replaces_and_drops_journals() {
+        let dir = tempdir().unwrap();
+        let live = live_database(dir.path());
+        fs::write(sidecar_path(&live, "-journal"), b"").unwrap();
+
+        let working_copy = WorkingCopy::new(&live).unwrap();
+        working_copy.open().unwrap().execute("UPDATE t SET v = 'edited' WHERE rowid = 1", []).unwrap();
+        working_copy.write_back(&live).unwrap();
+
+        let conn = Connection::open(&live).unwrap();
+        let value: String = conn.query_row("SELECT v FROM t WHERE rowid = 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(value, "edited");
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, vec!["Web Data"]);
+    }
+
+    #[test]
+    fn write_back_deleted_leaves_no_trace() {
+        let dir = tempdir().unwrap();
+        let live = live_database(dir.path());
+
+        let working_copy = WorkingCopy::new(&live).unwrap();
+        working_copy.open().unwrap().execute("DELETE FROM t WHERE rowid > 10", []).unwrap();
+        let secure_deletion = working_copy.write_back_deleted(&live).unwrap();
+        assert!(secure_deletion.applied);
+
+        let bytes = fs::read(&live).unwrap();
+        assert!(!bytes.windows(15).any(|w| w == b"secretvalue1999"));
+        let conn = Connection::open(&live).unwrap();
+        let free_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0)).unwrap();
+        assert_eq!(free_pages, 0);
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::{
+    browsers::{ensure_browser_closed, group_by_login_data},
+    database::{SecureDeletion, WorkingCopy},
+    logins::{delete_by_identity, EntryRef},
+    tasks::{self, types::TaskState, Task},
+};
+
+#[derive(Serialize)]
+pub struct EntryOutcome {
+    #[serde(flatten)]
+    pub entry: EntryRef,
+    pub rows_deleted: usize,
+    pub secure_deletion: SecureDeletion,
+    pub error: Option<String>,
+}
+
+/// Deletes many saved logins at once. Entries are grouped by `Login Data`
+/// database so each one is copied, changed in a single transaction and
+/// written back only once. A failure affects only the entries of its group.
+#[tauri::command]
+pub fn delete_account_entries(entries: Vec<EntryRef>) -> Vec<EntryOutcome> {
+    delete_entries(entries, &Task::detached())
+}
+
+/// Runs [`delete_account_entries`] as a cancellable task and returns its id.
+/// Progress is reported per database.
+#[tauri::command]
+pub fn start_delete_account_entries(app: AppHandle, state: State<TaskState>, entries: Vec<EntryRef>) -> String {
+    tasks::spawn(app, &state, move |task| Ok(delete_entries(entries, task)))
+}
+
+pub fn delete_entries(entries: Vec<EntryRef>, task: &Task) -> Vec<EntryOutcome> {
+    let mut outcomes: Vec<EntryOutcome> = entries
+        .iter()
+        .map(|entry| EntryOutcome {
+            entry: entry.clone(),
+            rows_deleted: 0,
+            secure_deletion: SecureDeletion::default(),
+            error: None,
+        })
+        .collect();
+
+    let (groups, failures) = group_by_login_data(&entries);
+    for (index, e) in failures {
+        outcomes[index].error = Some(e);
+    }
+
+    let total = groups.len();
+    for (processed, group) in groups.into_iter().enumerate() {
+        task.progress(&group.browser, &entries[group.indices[0]].profile, processed, total);
+
+        let result = task
+            .check()
+            .and_then(|_| ensure_browser_closed(&group.browser))
+            .and_then(|_| delete_group(&group.login_data, &entries, &group.indices, task));
+        match result {
+            Ok((rows, secure_deletion)) => {
+                for (index, rows_deleted) in group.indices.into_iter().zip(rows) {
+                    outcomes[index].rows_deleted = rows_deleted;
+                    outcomes[index].secure_deletion = secure_deletion.clone();
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to delete entries from {}: {}", group.login_data.display(), e);
+                for index in group.indices {
+                    outcomes[index].error = Some(e.clone());
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+fn delete_group(login_data: &Path, entries: &[EntryRef], indices: &[usize], task: &Task) -> Result<(Vec<usize>, SecureDeletion), String> {
+    let working_copy = WorkingCopy::new(login_data)?;
+
+    let rows = {
+        let mut conn = working_copy.open()?;
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut rows = Vec::with_capacity(indices.len());
+        for &index in indices {
+            rows.push(delete_by_identity(&tx, &entries[index].url, &entries[index].username)?);
+        }
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        rows
+    }; // conn is dropped here, closing the file handle
+
+    if rows.iter().all(|&n| n == 0) {
+        return Ok((rows, SecureDeletion::default()));
+    }
+    task.check()?;
+    let secure_deletion = working_copy.write_back_deleted(login_data)?;
+    Ok((rows, secure_deletion))
+}
@@ -1,92 +1,151 @@
+use std::path::PathBuf;
 
+use serde::Serialize;
+use tauri::{AppHandle, State};
 
-use crate::delete_profile::delete_profile;
-use std::{env, fs, path::Path};
+use crate::{
+    browsers::{browser_path, chromium_browsers, ensure_browser_closed, profile_names, profile_path, read_local_state},
+    database::SecureDeletion,
+    delete_profile::clear_login_data,
+    tasks::{self, types::TaskState, Task, CANCELLED},
+};
 
-struct Browsers {
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProfileDeletionStatus {
+    Deleted { rows_deleted: usize, secure_deletion: SecureDeletion },
+    Skipped { reason: String },
+    Failed { error: String },
+    Cancelled,
+}
+
+/// Outcome for one profile, or for a whole browser when `profile` is `None`
+/// (not installed, running, or its `Local State` is unreadable).
+#[derive(Serialize)]
+pub struct ProfileDeletionResult {
+    pub browser: String,
+    pub profile: Option<String>,
+    #[serde(flatten)]
+    pub status: ProfileDeletionStatus,
+}
+
+#[derive(Serialize)]
+pub struct DeleteAllReport {
+    pub total_deleted: usize,
+    pub results: Vec<ProfileDeletionResult>,
+}
+
+fn browser_result(browser: &str, status: ProfileDeletionStatus) -> ProfileDeletionResult {
+    ProfileDeletionResult {
+        browser: browser.to_string(),
+        profile: None,
+        status,
+    }
+}
+
+/// The profiles of one browser selected for deletion.
+struct BrowserTarget {
     name: String,
-    path: String,
-    root: String,
+    profiles: Vec<(String, PathBuf)>,
 }
 
-#[tauri::command]
-pub fn delete_all_passwords() -> Result<(), String> {
-    println!("Starting password deletion for all profiles.");
-    let local_appdata = env::var("LOCALAPPDATA").map_err(|e| format!("Failed to get LOCALAPPDATA: {}", e))?;
-    let roaming_appdata = env::var("APPDATA").map_err(|e| format!("Failed to get APPDATA: {}", e))?;
-
-    let browsers: Vec<Browsers> = vec![
-        Browsers {
-            name: "Brave Browser".to_string(),
-            path: "BraveSoftware\\Brave-Browser\\User Data".to_string(),
-            root: local_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Chromium".to_string(),
-            path: "Chromium\\User Data".to_string(),
-            root: local_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Google Chrome".to_string(),
-            path: "Google\\Chrome\\User Data".to_string(),
-            root: local_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Microsoft Edge".to_string(),
-            path: "Microsoft\\Edge\\User Data".to_string(),
-            root: local_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Opera".to_string(),
-            path: "Opera Software\\Opera Stable".to_string(),
-            root: roaming_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Opera GX".to_string(),
-            path: "Opera Software\\Opera GX Stable".to_string(),
-            root: roaming_appdata.to_owned(),
-        },
-        Browsers {
-            name: "Vivaldi".to_string(),
-            path: "Vivaldi\\User Data".to_string(),
-            root: local_appdata.to_owned(),
-        },
-    ];
-
-    for browser in browsers {
-        let browser_path = Path::new(&browser.root).join(&browser.path);
-        if !Path::exists(&browser_path) {
+pub fn delete_all(browsers: Option<Vec<String>>, profiles: Option<Vec<String>>, task: &Task) -> Result<DeleteAllReport, String> {
+    let known = chromium_browsers()?;
+    if let Some(unknown) = browsers.iter().flatten().find(|name| !known.iter().any(|b| &b.name == *name)) {
+        return Err(format!("Browser not found: {}", unknown));
+    }
+
+    let mut report = DeleteAllReport {
+        total_deleted: 0,
+        results: Vec::new(),
+    };
+
+    let mut targets = Vec::new();
+    for browser in known {
+        if browsers.as_ref().is_some_and(|names| !names.contains(&browser.name)) {
             continue;
         }
 
-        let local_state_path = Path::new(&browser_path).join("Local State");
-        if !Path::exists(&local_state_path) {
+        let browser_path = browser_path(&browser);
+        if !browser_path.exists() {
+            if browsers.is_some() {
+                report.results.push(browser_result(&browser.name, ProfileDeletionStatus::Skipped { reason: "Browser is not installed".to_string() }));
+            }
             continue;
         }
 
-        let local_state_file_result = fs::File::open(&local_state_path);
-        let local_state_file = match local_state_file_result {
-            Ok(file) => file,
-            Err(_) => continue,
-        };
-
-        let local_state_json: serde_json::Value = match serde_json::from_reader(local_state_file) {
+        let local_state_json = match read_local_state(&browser_path) {
             Ok(json) => json,
-            Err(_) => continue,
+            Err(error) => {
+                report.results.push(browser_result(&browser.name, ProfileDeletionStatus::Failed { error }));
+                continue;
+            }
         };
 
-        if let Some(info_cache) = local_state_json["profile"]["info_cache"].as_object() {
-            for (_dir_name, profile_info) in info_cache {
-                if let Some(profile_name) = profile_info["name"].as_str() {
-                    match delete_profile(browser.name.clone(), profile_name.to_string()) {
-                        Ok(_) => println!("Successfully deleted passwords for profile '{}' in {}", profile_name, browser.name),
-                        Err(e) => eprintln!("Failed to delete passwords for profile '{}' in {}: {}", profile_name, browser.name, e),
+        if let Err(reason) = ensure_browser_closed(&browser.name) {
+            report.results.push(browser_result(&browser.name, ProfileDeletionStatus::Skipped { reason }));
+            continue;
+        }
+
+        let selected = profile_names(&local_state_json)
+            .into_iter()
+            .filter(|(_, name)| profiles.as_ref().is_none_or(|names| names.contains(name)))
+            .map(|(dir, name)| (name, profile_path(&browser, &browser_path, &dir).join("Login Data")))
+            .collect();
+        targets.push(BrowserTarget { name: browser.name, profiles: selected });
+    }
+
+    let total = targets.iter().map(|t| t.profiles.len()).sum();
+    let mut processed = 0;
+
+    for target in targets {
+        let mut cleared: Vec<(PathBuf, String)> = Vec::new();
+        for (profile_name, login_data) in target.profiles {
+            task.progress(&target.name, &profile_name, processed, total);
+            processed += 1;
+
+            let status = if task.is_cancelled() {
+                ProfileDeletionStatus::Cancelled
+            } else if let Some((_, other)) = cleared.iter().find(|(path, _)| *path == login_data) {
+                // Opera GX keeps a single `Login Data` for all of its profiles.
+                ProfileDeletionStatus::Skipped { reason: format!("Shares Login Data with profile '{}'", other) }
+            } else if !login_data.exists() {
+                ProfileDeletionStatus::Skipped { reason: "Profile has no Login Data".to_string() }
+            } else {
+                match clear_login_data(&login_data, task) {
+                    Ok(deletion) => {
+                        report.total_deleted += deletion.rows_deleted;
+                        cleared.push((login_data, profile_name.clone()));
+                        ProfileDeletionStatus::Deleted {
+                            rows_deleted: deletion.rows_deleted,
+                            secure_deletion: deletion.secure_deletion,
+                        }
                     }
+                    Err(error) if error == CANCELLED => ProfileDeletionStatus::Cancelled,
+                    Err(error) => ProfileDeletionStatus::Failed { error },
                 }
-            }
+            };
+
+            report.results.push(ProfileDeletionResult {
+                browser: target.name.clone(),
+                profile: Some(profile_name),
+                status,
+            });
         }
     }
-    println!("Finished password deletion for all profiles.");
-    Ok(())
+
+    Ok(report)
+}
+
+/// Deletes every saved login of the selected browsers and profiles, all of
+/// them when a filter is omitted. Profiles are matched by display name.
+#[tauri::command]
+pub fn delete_all_passwords(browsers: Option<Vec<String>>, profiles: Option<Vec<String>>) -> Result<DeleteAllReport, String> {
+    delete_all(browsers, profiles, &Task::detached())
 }
 
+/// Runs [`delete_all_passwords`] as a cancellable task and returns its id.
+#[tauri::command]
+pub fn start_delete_all_passwords(app: AppHandle, state: State<TaskState>, browsers: Option<Vec<String>>, profiles: Option<Vec<String>>) -> String {
+    tasks::spawn(app, &state, move |task| delete_all(browsers, profiles, task))
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    browsers::{ensure_browser_closed, group_by_login_data},
+    database::WorkingCopy,
+    logins::{update_by_identity, EntryRef, LoginChanges},
+};
+
+#[derive(Deserialize, Clone)]
+pub struct EntryEdit {
+    #[serde(flatten)]
+    pub entry: EntryRef,
+    pub changes: LoginChanges,
+}
+
+#[derive(Serialize)]
+pub struct EditOutcome {
+    #[serde(flatten)]
+    pub entry: EntryRef,
+    pub rows_updated: usize,
+    pub error: Option<String>,
+}
+
+fn validate(changes: &LoginChanges) -> Result<(), String> {
+    if changes.username.is_none() && changes.origin_url.is_none() && changes.signon_realm.is_none() && changes.action_url.is_none() {
+        return Err("No changes given".to_string());
+    }
+
+    for url in [&changes.origin_url, &changes.signon_realm].into_iter().flatten() {
+        Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    }
+    // Chromium stores an empty `action_url` for logins saved outside a form.
+    if let Some(action_url) = changes.action_url.as_deref().filter(|url| !url.is_empty()) {
+        Url::parse(action_url).map_err(|e| format!("Invalid URL '{}': {}", action_url, e))?;
+    }
+    Ok(())
+}
+
+/// Rewrites the username and URLs of saved logins, e.g. after a domain or
+/// email change. Edits are grouped and applied like `delete_account_entries`:
+/// one transaction and one atomic write-back per `Login Data` database, with
+/// the browser required to be closed.
+#[tauri::command]
+pub fn edit_account_entries(edits: Vec<EntryEdit>) -> Vec<EditOutcome> {
+    let mut outcomes: Vec<EditOutcome> = edits
+        .iter()
+        .map(|edit| EditOutcome {
+            entry: edit.entry.clone(),
+            rows_updated: 0,
+            error: validate(&edit.changes).err(),
+        })
+        .collect();
+
+    let valid: Vec<usize> = (0..edits.len()).filter(|&index| outcomes[index].error.is_none()).collect();
+    let entries: Vec<EntryRef> = valid.iter().map(|&index| edits[index].entry.clone()).collect();
+
+    let (groups, failures) = group_by_login_data(&entries);
+    for (index, e) in failures {
+        outcomes[valid[index]].error = Some(e);
+    }
+
+    for group in groups {
+        let indices: Vec<usize> = group.indices.iter().map(|&index| valid[index]).collect();
+        let result = ensure_browser_closed(&group.browser).and_then(|_| edit_group(&group.login_data, &edits, &indices));
+        match result {
+            Ok(rows) => {
+                for (index, rows_updated) in indices.into_iter().zip(rows) {
+                    outcomes[index].rows_updated = rows_updated;
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to edit entries in {}: {}", group.login_data.display(), e);
+                for index in indices {
+                    outcomes[index].error = Some(e.clone());
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+fn edit_group(login_data: &Path, edits: &[EntryEdit], indices: &[usize]) -> Result<Vec<usize>, String> {
+    let working_copy = WorkingCopy::new(login_data)?;
+
+    let rows = {
+        let mut conn = working_copy.open()?;
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut rows = Vec::with_capacity(indices.len());
+        for &index in indices {
+            let edit = &edits[index];
+            rows.push(update_by_identity(&tx, &edit.entry.url, &edit.entry.username, &edit.changes)?);
+        }
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        rows
+    }; // conn is dropped here, closing the file handle
+
+    working_copy.write_back(login_data)?;
+    Ok(rows)
+}
@@ -1,38 +1,129 @@
+mod browsers;
+mod database;
+mod logins;
+mod mozilla;
+
+mod tasks;
+use tasks::{cancel_task, types::TaskState};
+
 mod delete_all_passwords;
-use delete_all_passwords::delete_all_passwords;
+use delete_all_passwords::{delete_all_passwords, start_delete_all_passwords};
 
 
 mod delete_profile;
 use delete_profile::delete_profile;
 
+mod remove_profile;
+use remove_profile::remove_profile;
+
 mod passwords;
-use passwords::passwords;
+use passwords::{passwords, start_scan};
 
 mod totp;
-use totp::{add_account, delete_account, get_accounts_with_codes, AppState};
+use totp::{
+    add_account,
+    backups::{cancel_import, commit_import, preview_import},
+    create_totp_store, delete_account,
+    export::export_accounts,
+    get_accounts_with_codes,
+    hotp::{next_hotp_code, resync_hotp},
+    import::import_uris,
+    lock_totp_store,
+    migration::import_migration,
+    qr::{account_qr_code, scan_qr_image},
+    reorder_accounts, totp_store_status,
+    types::AppState,
+    unlock_totp_store, update_account,
+};
 
 mod delete_account_entry;
 use delete_account_entry::delete_account_entry;
 
-use std::sync::Mutex;
+mod delete_account_entries;
+use delete_account_entries::{delete_account_entries, start_delete_account_entries};
+
+mod edit_account_entries;
+use edit_account_entries::edit_account_entries;
+
+mod transfer_account_entries;
+use transfer_account_entries::transfer_account_entries;
+
+mod autofill;
+use autofill::{autofill_entries, delete_autofill_entries};
+
+mod site_data;
+use site_data::{cookie_hosts, delete_site_data};
+
+mod password_manager;
+use password_manager::{password_manager_states, set_password_manager};
+
+mod policies;
+use policies::{install_managed_policies, remove_managed_policies};
+
+mod payment_data;
+use payment_data::{delete_payment_data, payment_data};
+
+mod plan;
+use plan::{execute_plan, plan_delete_account_entry, plan_delete_all_passwords, plan_delete_profile, types::PlanState};
+
+mod cleanup;
+use cleanup::plan_cleanup;
 
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(AppState {
-            accounts: Mutex::new(vec![]),
-            next_id: Mutex::new(0),
-        })
+        .manage(AppState::default())
+        .manage(PlanState::default())
+        .manage(TaskState::default())
         .invoke_handler(tauri::generate_handler![
             delete_all_passwords,
             delete_profile,
+            remove_profile,
             passwords,
             add_account,
             delete_account,
             get_accounts_with_codes,
-            delete_account_entry
+            totp_store_status,
+            create_totp_store,
+            unlock_totp_store,
+            lock_totp_store,
+            next_hotp_code,
+            resync_hotp,
+            import_migration,
+            import_uris,
+            scan_qr_image,
+            account_qr_code,
+            update_account,
+            reorder_accounts,
+            preview_import,
+            commit_import,
+            cancel_import,
+            export_accounts,
+            delete_account_entry,
+            delete_account_entries,
+            edit_account_entries,
+            transfer_account_entries,
+            autofill_entries,
+            delete_autofill_entries,
+            cookie_hosts,
+            delete_site_data,
+            password_manager_states,
+            set_password_manager,
+            install_managed_policies,
+            remove_managed_policies,
+            payment_data,
+            delete_payment_data,
+            plan_delete_all_passwords,
+            plan_delete_profile,
+            plan_delete_account_entry,
+            plan_cleanup,
+            execute_plan,
+            start_scan,
+            start_delete_all_passwords,
+            start_delete_account_entries,
+            cancel_task
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,270 @@
+use rusqlite::{params, params_from_iter, Connection, Params};
+use serde::{Deserialize, Serialize};
+
+use crate::cleanup::CompiledRule;
+
+/// Which rows of the `logins` table an operation applies to.
+#[derive(Clone)]
+pub enum LoginFilter {
+    All,
+    Entry { url: String, username: String },
+    Rule(CompiledRule),
+}
+
+/// Identity of a saved login row.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct LoginEntry {
+    pub id: i64,
+    pub url: String,
+    pub username: String,
+}
+
+/// The metadata columns of a `logins` row. Timestamps are WebKit
+/// microseconds, `0` when the browser never set them.
+pub struct LoginRow {
+    pub id: i64,
+    pub url: String,
+    pub origin_url: String,
+    pub username: String,
+    pub date_created: i64,
+    pub date_password_modified: i64,
+    pub date_last_used: i64,
+    pub times_used: i64,
+}
+
+impl From<LoginRow> for LoginEntry {
+    fn from(row: LoginRow) -> Self {
+        LoginEntry {
+            id: row.id,
+            url: row.url,
+            username: row.username,
+        }
+    }
+}
+
+impl LoginFilter {
+    fn where_clause(&self) -> (&'static str, Vec<String>) {
+        match self {
+            LoginFilter::All | LoginFilter::Rule(_) => ("1 = 1", vec![]),
+            LoginFilter::Entry { url, username } => ("signon_realm = ?1 AND username_value = ?2", vec![url.clone(), username.clone()]),
+        }
+    }
+
+    fn matches(&self, row: &LoginRow) -> bool {
+        match self {
+            LoginFilter::Rule(rule) => rule.matches(row),
+            _ => true,
+        }
+    }
+}
+
+/// Metadata columns that older `Login Data` schemas may lack. Missing ones
+/// read as `0` unless the filter needs them.
+const OPTIONAL_COLUMNS: [&str; 3] = ["date_password_modified", "date_last_used", "times_used"];
+
+fn login_columns(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare("PRAGMA table_info(logins)").map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to execute SQL statement: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read logins schema: {}", e))?;
+    Ok(columns)
+}
+
+pub fn select_rows(conn: &Connection, filter: &LoginFilter) -> Result<Vec<LoginRow>, String> {
+    let available = login_columns(conn)?;
+    let required = match filter {
+        LoginFilter::Rule(rule) => rule.required_columns(),
+        _ => vec![],
+    };
+
+    let mut optional = Vec::new();
+    for column in OPTIONAL_COLUMNS {
+        if available.iter().any(|c| c == column) {
+            optional.push(column);
+        } else if required.contains(&column) {
+            return Err(format!("Login Data has no '{}' column; this browser version does not record it", column));
+        } else {
+            optional.push("0");
+        }
+    }
+
+    let (clause, params) = filter.where_clause();
+    let sql = format!(
+        "SELECT rowid, signon_realm, origin_url, username_value, date_created, {} FROM logins WHERE {} ORDER BY rowid",
+        optional.join(", "),
+        clause
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let rows = stmt
+        .query_map(params_from_iter(params), |row| {
+            Ok(LoginRow {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                origin_url: row.get(2)?,
+                username: row.get(3)?,
+                date_created: row.get(4)?,
+                date_password_modified: row.get(5)?,
+                date_last_used: row.get(6)?,
+                times_used: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+
+    let rows = rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read logins: {}", e))?;
+    Ok(rows.into_iter().filter(|row| filter.matches(row)).collect())
+}
+
+pub fn select_entries(conn: &Connection, filter: &LoginFilter) -> Result<Vec<LoginEntry>, String> {
+    Ok(select_rows(conn, filter)?.into_iter().map(LoginEntry::from).collect())
+}
+
+/// Tables whose rows belong to a `logins` row through `parent_id`. Older
+/// schemas may lack them.
+pub const DEPENDENT_TABLES: [&str; 2] = ["password_notes", "insecure_credentials"];
+
+pub fn has_table(conn: &Connection, table: &str) -> Result<bool, String> {
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1", [table], |row| row.get::<_, i64>(0))
+        .map(|count| count > 0)
+        .map_err(|e| format!("Failed to read database schema: {}", e))
+}
+
+/// Deletes the dependent rows of the logins matching `condition`, which
+/// must run before the logins themselves are deleted.
+fn delete_dependents<P: Params + Clone>(conn: &Connection, condition: &str, params: P) -> Result<(), String> {
+    for table in DEPENDENT_TABLES {
+        if has_table(conn, table)? {
+            conn.execute(&format!("DELETE FROM {} WHERE parent_id IN (SELECT id FROM logins WHERE {})", table, condition), params.clone())
+                .map_err(|e| format!("Failed to delete from {}: {}", table, e))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn delete_entries(conn: &Connection, entries: &[LoginEntry]) -> Result<usize, String> {
+    let mut stmt = conn.prepare("DELETE FROM logins WHERE rowid = ?1").map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+
+    let mut rows_affected = 0;
+    for entry in entries {
+        delete_dependents(conn, "rowid = ?1", [entry.id])?;
+        rows_affected += stmt.execute([entry.id]).map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+    }
+    Ok(rows_affected)
+}
+
+/// Deletes every saved login together with its dependent rows.
+pub fn delete_all(conn: &Connection) -> Result<usize, String> {
+    delete_dependents(conn, "1 = 1", [])?;
+    conn.execute("DELETE FROM logins", []).map_err(|e| format!("Failed to delete all passwords from profile: {}", e))
+}
+
+/// A saved login as identified by the frontend: the profile it belongs to
+/// and its `signon_realm`/`username_value` pair.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EntryRef {
+    pub browser: String,
+    pub profile: String,
+    pub url: String,
+    pub username: String,
+}
+
+pub fn delete_by_identity(conn: &Connection, url: &str, username: &str) -> Result<usize, String> {
+    delete_dependents(conn, "signon_realm = ?1 AND username_value = ?2", [url, username])?;
+    let mut stmt = conn.prepare("DELETE FROM logins WHERE signon_realm = ?1 AND username_value = ?2").map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    stmt.execute([url, username]).map_err(|e| format!("Failed to execute SQL statement: {}", e))
+}
+
+/// New values for the metadata columns of a saved login. Unset fields keep
+/// their current value; the encrypted password is never touched.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LoginChanges {
+    pub username: Option<String>,
+    pub origin_url: Option<String>,
+    pub signon_realm: Option<String>,
+    pub action_url: Option<String>,
+}
+
+pub fn update_by_identity(conn: &Connection, url: &str, username: &str, changes: &LoginChanges) -> Result<usize, String> {
+    let mut stmt = conn
+        .prepare(
+            "UPDATE logins SET username_value = COALESCE(?3, username_value), origin_url = COALESCE(?4, origin_url), \
+             signon_realm = COALESCE(?5, signon_realm), action_url = COALESCE(?6, action_url) \
+             WHERE signon_realm = ?1 AND username_value = ?2",
+        )
+        .map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+
+    stmt.execute(params![url, username, changes.username, changes.origin_url, changes.signon_realm, changes.action_url])
+        .map_err(|e| format!("Failed to execute SQL statement: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleanup::types::CleanupRule;
+
+    fn legacy_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE logins (origin_url TEXT, signon_realm TEXT, username_value TEXT, date_created INTEGER, times_used INTEGER);
+             INSERT INTO logins VALUES ('https://example.com/', 'https://example.com/', 'alice', 1, 3);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn database_with_notes() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE logins (id INTEGER PRIMARY KEY, origin_url TEXT, signon_realm TEXT, username_value TEXT, date_created INTEGER);
+             CREATE TABLE password_notes (id INTEGER PRIMARY KEY, parent_id INTEGER NOT NULL, value BLOB);
+             CREATE TABLE insecure_credentials (parent_id INTEGER, insecurity_type INTEGER NOT NULL);
+             INSERT INTO logins VALUES (1, 'https://a.com/', 'https://a.com/', 'alice', 1), (2, 'https://b.com/', 'https://b.com/', 'bob', 1);
+             INSERT INTO password_notes VALUES (1, 1, x'00'), (2, 2, x'00');
+             INSERT INTO insecure_credentials VALUES (1, 0), (2, 0);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn dependents_of(conn: &Connection, id: i64) -> i64 {
+        conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM password_notes WHERE parent_id = ?1) + (SELECT COUNT(*) FROM insecure_credentials WHERE parent_id = ?1)",
+            [id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn deleting_entries_removes_their_dependents() {
+        let conn = database_with_notes();
+        let entries = select_entries(&conn, &LoginFilter::Entry { url: "https://a.com/".to_string(), username: "alice".to_string() }).unwrap();
+        assert_eq!(delete_entries(&conn, &entries).unwrap(), 1);
+        assert_eq!(dependents_of(&conn, 1), 0);
+        assert_eq!(dependents_of(&conn, 2), 2);
+
+        assert_eq!(delete_all(&conn).unwrap(), 1);
+        assert_eq!(dependents_of(&conn, 2), 0);
+    }
+
+    #[test]
+    fn missing_columns_read_as_zero() {
+        let rows = select_rows(&legacy_database(), &LoginFilter::All).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].times_used, 3);
+        assert_eq!(rows[0].date_last_used, 0);
+    }
+
+    #[test]
+    fn rule_needing_a_missing_column_fails() {
+        let rule = CleanupRule {
+            unused_days: Some(30),
+            ..Default::default()
+        };
+        let filter = LoginFilter::Rule(CompiledRule::compile(&rule).unwrap());
+        assert!(select_rows(&legacy_database(), &filter).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::passwords::types::Browsers;
+
+/// A profile of a Firefox-based browser, as listed in its `profiles.ini`.
+pub struct MozillaProfile {
+    pub browser: String,
+    pub profile: String,
+    pub path: PathBuf,
+}
+
+pub fn mozilla_browsers() -> Result<Vec<Browsers>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let roaming_appdata = env::var("APPDATA").map_err(|e| format!("Failed to get APPDATA: {}", e))?;
+        Ok(vec![
+            Browsers {
+                name: "Mozilla Firefox".to_string(),
+                path: "Mozilla\\Firefox".to_string(),
+                root: roaming_appdata.to_owned(),
+            },
+            Browsers {
+                name: "LibreWolf".to_string(),
+                path: "librewolf".to_string(),
+                root: roaming_appdata.to_owned(),
+            },
+            Browsers {
+                name: "Waterfox".to_string(),
+                path: "Waterfox".to_string(),
+                root: roaming_appdata.to_owned(),
+            },
+        ])
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let home = env::var("HOME").map_err(|e| format!("Failed to get HOME: {}", e))?;
+        Ok(vec![
+            Browsers {
+                name: "Mozilla Firefox".to_string(),
+                path: ".mozilla/firefox".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "LibreWolf".to_string(),
+                path: ".librewolf".to_string(),
+                root: home.to_owned(),
+            },
+            Browsers {
+                name: "Waterfox".to_string(),
+                path: ".waterfox".to_string(),
+                root: home.to_owned(),
+            },
+        ])
+    }
+}
+
+pub fn is_mozilla_browser(browser_name: &str) -> bool {
+    mozilla_browsers().is_ok_and(|browsers| browsers.iter().any(|b| b.name == browser_name))
+}
+
+/// Reads the `[ProfileN]` sections of `profiles.ini`.
+fn parse_profiles_ini(browser: &Browsers, root: &Path, contents: &str) -> Vec<MozillaProfile> {
+    let mut profiles = Vec::new();
+    let mut section: Option<(Option<String>, Option<String>, bool)> = None;
+
+    let mut finish = |section: Option<(Option<String>, Option<String>, bool)>| {
+        if let Some((Some(name), Some(path), is_relative)) = section {
+            profiles.push(MozillaProfile {
+                browser: browser.name.clone(),
+                profile: name,
+                path: if is_relative { root.join(path) } else { PathBuf::from(path) },
+            });
+        }
+    };
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            finish(section.take());
+            if line.starts_with("[Profile") {
+                section = Some((None, None, true));
+            }
+        } else if let (Some((name, path, is_relative)), Some((key, value))) = (section.as_mut(), line.split_once('=')) {
+            match key {
+                "Name" => *name = Some(value.to_string()),
+                "Path" => *path = Some(value.to_string()),
+                "IsRelative" => *is_relative = value != "0",
+                _ => {}
+            }
+        }
+    }
+    finish(section);
+
+    profiles
+}
+
+pub fn mozilla_profiles() -> Result<Vec<MozillaProfile>, String> {
+    let mut profiles = Vec::new();
+    for browser in mozilla_browsers()? {
+        let root = Path::new(&browser.root).join(&browser.path);
+        let Ok(contents) = fs::read_to_string(root.join("profiles.ini")) else {
+            continue;
+        };
+        profiles.extend(parse_profiles_ini(&browser, &root, &contents).into_iter().filter(|profile| profile.path.exists()));
+    }
+    Ok(profiles)
+}
+
+pub fn locate_mozilla_profile(browser_name: &str, profile_name: &str) -> Result<MozillaProfile, String> {
+    mozilla_profiles()?
+        .into_iter()
+        .find(|profile| profile.browser == browser_name && profile.profile == profile_name)
+        .ok_or_else(|| format!("Could not find profile '{}' in {}", profile_name, browser_name))
+}
+
+/// Firefox holds `parent.lock` open without sharing on Windows and keeps a
+/// `lock` symlink to `<ip>:+<pid>` elsewhere while a profile is in use.
+#[cfg(target_os = "windows")]
+fn is_running(profile_path: &Path) -> bool {
+    let lockfile = profile_path.join("parent.lock");
+    lockfile.exists() && fs::OpenOptions::new().write(true).open(&lockfile).is_err()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_running(profile_path: &Path) -> bool {
+    let Ok(target) = fs::read_link(profile_path.join("lock")) else {
+        return false;
+    };
+
+    if cfg!(target_os = "linux") {
+        let target = target.to_string_lossy();
+        match target.rsplit('+').next().and_then(|pid| pid.parse::<u32>().ok()) {
+            Some(pid) => Path::new("/proc").join(pid.to_string()).exists(),
+            None => true,
+        }
+    } else {
+        true
+    }
+}
+
+pub fn ensure_mozilla_closed(profile: &MozillaProfile) -> Result<(), String> {
+    if is_running(&profile.path) {
+        return Err(format!("{} is using profile '{}'; close it before modifying its data", profile.browser, profile.profile));
+    }
+    Ok(())
+}
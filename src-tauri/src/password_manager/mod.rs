@@ -0,0 +1,121 @@
+use std::{fs, io::Write, path::Path};
+
+pub mod types;
+
+use crate::{
+    browsers::{all_profiles, ensure_browser_closed, resolve_profile},
+    database::replace_file,
+    mozilla::{ensure_mozilla_closed, is_mozilla_browser, locate_mozilla_profile, mozilla_profiles},
+};
+use types::{PasswordManagerState, PasswordManagerStatus};
+
+const REMEMBER_SIGNONS: &str = "signon.rememberSignons";
+
+fn read_preferences(profile_path: &Path) -> Result<serde_json::Value, String> {
+    let preferences_path = profile_path.join("Preferences");
+    let preferences_file = fs::File::open(&preferences_path).map_err(|e| format!("Failed to open Preferences file: {}", e))?;
+    serde_json::from_reader(preferences_file).map_err(|e| format!("Failed to parse Preferences JSON: {}", e))
+}
+
+/// Both settings default to on when Chromium has never written them.
+pub fn chromium_state(profile_path: &Path) -> PasswordManagerState {
+    let preferences = read_preferences(profile_path).unwrap_or_default();
+    PasswordManagerState {
+        enabled: preferences["credentials_enable_service"].as_bool().unwrap_or(true),
+        auto_sign_in: Some(preferences["credentials_enable_autosignin"].as_bool().unwrap_or(true)),
+    }
+}
+
+/// The last `user_pref("signon.rememberSignons", ...)` line wins, as it does
+/// when Firefox loads the file.
+fn remember_signons(contents: &str) -> Option<bool> {
+    contents
+        .lines()
+        .filter(|line| line.trim_start().starts_with("user_pref(") && line.contains(&format!("\"{}\"", REMEMBER_SIGNONS)))
+        .filter_map(|line| {
+            if line.contains("false") {
+                Some(false)
+            } else if line.contains("true") {
+                Some(true)
+            } else {
+                None
+            }
+        })
+        .next_back()
+}
+
+/// `user.js` overrides `prefs.js`, which Firefox rewrites on exit.
+fn mozilla_state(profile_path: &Path) -> PasswordManagerState {
+    let enabled = ["user.js", "prefs.js"]
+        .iter()
+        .find_map(|file| fs::read_to_string(profile_path.join(file)).ok().and_then(|contents| remember_signons(&contents)))
+        .unwrap_or(true);
+
+    PasswordManagerState { enabled, auto_sign_in: None }
+}
+
+fn set_chromium_state(profile_path: &Path, enabled: bool) -> Result<(), String> {
+    let mut preferences = read_preferences(profile_path)?;
+    let preferences_object = preferences.as_object_mut().ok_or("Preferences is not a JSON object")?;
+    preferences_object.insert("credentials_enable_service".to_string(), enabled.into());
+    preferences_object.insert("credentials_enable_autosignin".to_string(), enabled.into());
+
+    let contents = serde_json::to_vec(&preferences).map_err(|e| format!("Failed to serialize Preferences: {}", e))?;
+    replace_file(&profile_path.join("Preferences"), |file| file.write_all(&contents))
+}
+
+fn set_mozilla_state(profile_path: &Path, enabled: bool) -> Result<(), String> {
+    let user_js = profile_path.join("user.js");
+    let existing = fs::read_to_string(&user_js).unwrap_or_default();
+
+    let mut contents: String = existing
+        .lines()
+        .filter(|line| !line.contains(&format!("\"{}\"", REMEMBER_SIGNONS)))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    contents.push_str(&format!("user_pref(\"{}\", {});\n", REMEMBER_SIGNONS, enabled));
+
+    replace_file(&user_js, |file| file.write_all(contents.as_bytes()))
+}
+
+#[tauri::command]
+pub fn password_manager_states() -> Result<Vec<PasswordManagerStatus>, String> {
+    let mut states = Vec::new();
+
+    if let Ok(locations) = all_profiles() {
+        for location in locations {
+            states.push(PasswordManagerStatus {
+                state: chromium_state(&location.profile_path),
+                browser: location.browser,
+                profile: location.profile,
+            });
+        }
+    }
+
+    for profile in mozilla_profiles()? {
+        states.push(PasswordManagerStatus {
+            state: mozilla_state(&profile.path),
+            browser: profile.browser,
+            profile: profile.profile,
+        });
+    }
+
+    Ok(states)
+}
+
+/// Turns the browser's own password manager on or off for one profile. The
+/// browser must be closed, as it rewrites its preferences on exit.
+#[tauri::command]
+pub fn set_password_manager(browser_name: String, profile_name: String, enabled: bool) -> Result<PasswordManagerState, String> {
+    if is_mozilla_browser(&browser_name) {
+        let profile = locate_mozilla_profile(&browser_name, &profile_name)?;
+        ensure_mozilla_closed(&profile)?;
+        set_mozilla_state(&profile.path, enabled)?;
+        return Ok(mozilla_state(&profile.path));
+    }
+
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    ensure_browser_closed(&location.browser)?;
+    set_chromium_state(&location.profile_path, enabled)?;
+    Ok(chromium_state(&location.profile_path))
+}
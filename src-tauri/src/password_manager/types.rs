@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether the browser offers to save passwords and signs in automatically.
+/// Mozilla browsers have no automatic sign-in setting.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct PasswordManagerState {
+    pub enabled: bool,
+    pub auto_sign_in: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct PasswordManagerStatus {
+    pub browser: String,
+    pub profile: String,
+    #[serde(flatten)]
+    pub state: PasswordManagerState,
+}
@@ -1,8 +1,7 @@
-use std::path::Path;
-use std::{env, fs};
+use std::path::{Path, PathBuf};
 
 use chrono::DateTime;
-use rusqlite::Connection;
+use tauri::{AppHandle, State};
 
 pub mod types;
 pub mod utils;
@@ -10,135 +9,106 @@ pub mod utils;
 use types::{BrowserData, Browsers, Passwords, ProfileData};
 use utils::{webkit_to_unix_time};
 
-use tempfile::tempdir;
-
-#[tauri::command]
-pub fn passwords() -> String {
-
-  let browsers:Vec<Browsers>;
-  
-  #[cfg(target_os = "windows")]
-  {
-    let local_appdata = env::var("LOCALAPPDATA").unwrap();
-    let roaming_appdata = env::var("APPDATA").unwrap();
-    browsers = vec![
-      Browsers {
-        name: "Brave Browser".to_string(),
-        path: "BraveSoftware/Brave-Browser/User Data".to_string(),
-        root: local_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Chromium".to_string(),
-        path: "Chromium/User Data".to_string(),
-        root: local_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Google Chrome".to_string(),
-        path: "Google/Chrome/User Data".to_string(),
-        root: local_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Microsoft Edge".to_string(),
-        path: "Microsoft/Edge/User Data".to_string(),
-        root: local_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Opera".to_string(),
-        path: "Opera Software/Opera Stable".to_string(),
-        root: roaming_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Opera GX".to_string(),
-        path: "Opera Software/Opera GX Stable".to_string(),
-        root: roaming_appdata.to_owned(),
-      },
-      Browsers {
-        name: "Vivaldi".to_string(),
-        path: "Vivaldi/User Data".to_string(),
-        root: local_appdata.to_owned(),
-      },
-    ];
-  }
+use crate::{
+  browsers::{chromium_browsers, read_local_state},
+  database::WorkingCopy,
+  password_manager::chromium_state,
+  tasks::{self, types::TaskState, Task},
+};
+
+/// Reads from a working copy so logins still in the `-wal` or `-journal`
+/// file of a running browser are included.
+fn read_logins(login_data: &Path) -> Result<Vec<Passwords>, String> {
+  let working_copy = WorkingCopy::new(login_data)?;
+  let conn = working_copy.open()?;
+
+  let mut logins = conn
+    .prepare("SELECT signon_realm, username_value, date_created, date_password_modified FROM 'logins'")
+    .map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+  let rows = logins
+    .query_map([], |row| {
+      let url: String = row.get(0)?;
+      let username: String = row.get(1)?;
+      let date_created: i64 = row.get(2)?;
+      let date_password_modified: i64 = row.get(3)?;
+
+      Ok(Passwords {
+        url,
+        username,
+        date_created: DateTime::from_timestamp(webkit_to_unix_time(date_created), 0).unwrap_or_default(),
+        date_modified: DateTime::from_timestamp(webkit_to_unix_time(date_password_modified), 0).unwrap_or_default(),
+      })
+    })
+    .map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+
+  rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read logins: {}", e))
+}
 
-  #[cfg(target_os = "linux")]
-  {
-    let home = env::var("HOME").unwrap();
-    browsers = vec![
-      Browsers {
-        name: "Chromium".to_string(),
-        path: ".config/chromium".to_string(),
-        root: home.to_owned(),
-      },
-    ];
-  }
+/// Reads the saved logins of every profile, reporting progress per profile.
+/// A cancelled scan returns the profiles read so far. Browsers whose
+/// `Local State` cannot be read are skipped, and profiles whose `Login Data`
+/// cannot be read are listed without passwords and with the error.
+pub fn scan(task: &Task) -> Vec<BrowserData> {
+  let browsers: Vec<Browsers> = chromium_browsers().unwrap_or_default();
 
-  let mut browser_data: Vec<BrowserData> = Vec::new();
+  let mut installed: Vec<(Browsers, PathBuf, serde_json::Value)> = Vec::new();
   for browser in browsers {
-    let browser_path = Path::new(&browser.root).join(browser.path);
+    let browser_path = Path::new(&browser.root).join(&browser.path);
 
     if !Path::exists(&browser_path) {
       continue;
     }
 
-    let local_state_path = Path::new(&browser_path).join("Local State");
+    match read_local_state(&browser_path) {
+      Ok(json) => installed.push((browser, browser_path, json)),
+      Err(e) => eprintln!("Skipping {}: {}", browser.name, e),
+    }
+  }
 
-    let file = fs::File::open(local_state_path).expect("file error");
-    let json: serde_json::Value = serde_json::from_reader(file).expect("json error");
+  let total = installed
+    .iter()
+    .map(|(_, _, json)| json["profile"]["profiles_order"].as_array().map_or(0, |p| p.len()))
+    .sum();
+  let mut processed = 0;
 
-    let profiles = json["profile"]["profiles_order"].as_array().unwrap();
+  let mut browser_data: Vec<BrowserData> = Vec::new();
+  for (browser, browser_path, json) in installed {
+    let profiles = json["profile"]["profiles_order"].as_array().cloned().unwrap_or_default();
 
     let mut profile_data: Vec<ProfileData> = Vec::new();
 
-    for profile in profiles {
-      let profile_name = &json["profile"]["info_cache"][profile.as_str().unwrap()]["name"];
+    for profile in profiles.iter().filter_map(|p| p.as_str()) {
+      if task.is_cancelled() {
+        break;
+      }
 
-      let login_data;
+      let profile_name = &json["profile"]["info_cache"][profile]["name"];
+      task.progress(&browser.name, profile_name.as_str().unwrap_or_default(), processed, total);
+      processed += 1;
 
-      if browser.name == "Opera GX" {
-        login_data = Path::new(&browser_path).join("Login Data");
+      let login_data = if browser.name == "Opera GX" {
+        Path::new(&browser_path).join("Login Data")
       } else {
-        login_data = Path::new(&browser_path)
-          .join(profile.as_str().unwrap())
-          .join("Login Data");
-      }
+        Path::new(&browser_path)
+          .join(profile)
+          .join("Login Data")
+      };
 
-      let tmpdir = tempdir();
-      let tmpdir_expect = tmpdir.expect("cannot create tmp dir");
-      let tmp_login_data = tmpdir_expect.path().join("login_data");
-      let _ = fs::copy(login_data, &tmp_login_data);
-
-      let conn = Connection::open(tmp_login_data).expect("db error");
-
-      let mut logins = conn.prepare("SELECT signon_realm, username_value, date_created, date_password_modified FROM 'logins'").expect("sqlite error"); //err app crash browser open
-      let mut rows = logins.query([]).unwrap();
-      
-      let mut passwords_data: Vec<Passwords> = Vec::new();
-
-      while let Some(row) = rows.next().unwrap() {
-        let url: String = row.get(0).unwrap();
-        let username: String = row.get(1).unwrap();
-        let date_created: i64 = row.get(2).unwrap();
-        let date_password_modified: i64 = row.get(3).unwrap();
-
-        let date_created_timestamp =
-          DateTime::from_timestamp(webkit_to_unix_time(date_created), 0).unwrap();
-        let date_password_modified_timestamp =
-          DateTime::from_timestamp(webkit_to_unix_time(date_password_modified), 0)
-            .unwrap();
-
-        let data = Passwords {
-          url: url,
-          username: username,
-          date_created: date_created_timestamp,
-          date_modified: date_password_modified_timestamp,
-        };
-
-        passwords_data.push(data);
-      }
+      let password_manager = chromium_state(login_data.parent().unwrap_or(&browser_path));
+
+      let (passwords_data, error) = match read_logins(&login_data) {
+        Ok(passwords) => (passwords, None),
+        Err(e) => {
+          eprintln!("Failed to read logins of profile '{}' in {}: {}", profile, browser.name, e);
+          (Vec::new(), Some(e))
+        }
+      };
 
       let profile = ProfileData {
         profile_name: profile_name.to_string(),
         passwords: passwords_data,
+        password_manager,
+        error,
       };
 
       profile_data.push(profile);
@@ -152,9 +122,23 @@ pub fn passwords() -> String {
     browser_data.push(browser);
   }
 
+  browser_data
+}
+
+#[tauri::command]
+pub fn passwords() -> String {
+  let browser_data = scan(&Task::detached());
+
   let json_data = serde_json::to_string(&browser_data).expect("JSON error");
 
   // println!("{:?}", json_data)
 
-  json_data.into()
+  json_data
+}
+
+/// Runs the scan as a cancellable task and returns its id. The
+/// `task-finished` event carries the scanned browsers as its `result`.
+#[tauri::command]
+pub fn start_scan(app: AppHandle, state: State<TaskState>) -> String {
+  tasks::spawn(app, &state, |task| Ok(scan(task)))
 }
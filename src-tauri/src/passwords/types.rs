@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::password_manager::types::PasswordManagerState;
+
 #[derive(Serialize, Deserialize)]
 pub struct Passwords {
     pub url: String,
@@ -13,6 +15,9 @@ pub struct Passwords {
 pub struct ProfileData {
     pub profile_name: String,
     pub passwords: Vec<Passwords>,
+    pub password_manager: PasswordManagerState,
+    /// Why `Login Data` could not be read; `passwords` is then empty.
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -1,5 +1,9 @@
+const SECONDS_BETWEEN_1601_AND_1970: i64 = 11644473600;
+
 pub fn webkit_to_unix_time(webkit_time: i64) -> i64 {
-    let seconds_between19701601 = 11644473600;
+    webkit_time / 1000000 - SECONDS_BETWEEN_1601_AND_1970
+}
 
-    return webkit_time / 1000000 - seconds_between19701601;
-}
\ No newline at end of file
+pub fn unix_to_webkit_time(unix_time: i64) -> i64 {
+    (unix_time + SECONDS_BETWEEN_1601_AND_1970) * 1000000
+}
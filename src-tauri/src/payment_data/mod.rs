@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, Row};
+
+pub mod types;
+
+use crate::{
+    autofill::web_data_path,
+    browsers::{ensure_browser_closed, resolve_profile},
+    database::{DeletionReport, WorkingCopy},
+};
+use types::{PaymentCard, PaymentDataKind, PaymentDataRef, PaymentInventory, SavedAddress, SavedIban};
+
+/// Tables holding each kind of data, keyed by `guid`. Chromium moved
+/// addresses from `autofill_profiles` and its satellite tables to
+/// `local_addresses` in version 118; whichever exist are used.
+fn tables(kind: PaymentDataKind) -> &'static [&'static str] {
+    match kind {
+        PaymentDataKind::Card => &["credit_cards", "local_stored_cvc"],
+        PaymentDataKind::Address => &[
+            "local_addresses",
+            "local_addresses_type_tokens",
+            "autofill_profiles",
+            "autofill_profile_names",
+            "autofill_profile_emails",
+            "autofill_profile_phones",
+            "autofill_profile_addresses",
+            "autofill_profile_birthdates",
+        ],
+        PaymentDataKind::Iban => &["local_ibans"],
+    }
+}
+
+fn columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1)).map_err(|e| format!("Failed to read {} schema: {}", table, e))?;
+    names.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read {} schema: {}", table, e))
+}
+
+/// Selects `wanted` columns from `table`, substituting `NULL` for columns
+/// this Chromium version doesn't have. Missing tables yield no rows.
+fn query<T>(conn: &Connection, table: &str, wanted: &[&str], map: impl Fn(&Row) -> rusqlite::Result<T>) -> Result<Vec<T>, String> {
+    let existing = columns(conn, table)?;
+    if existing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let select = wanted
+        .iter()
+        .map(|column| if existing.iter().any(|c| c == column) { column.to_string() } else { "NULL".to_string() })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM {}", select, table)).map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let rows = stmt.query_map([], |row| map(row)).map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read {}: {}", table, e))
+}
+
+/// Web Data stores these dates as Unix seconds.
+fn timestamp(row: &Row, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    Ok(DateTime::from_timestamp(row.get::<_, Option<i64>>(index)?.unwrap_or_default(), 0).unwrap_or_default())
+}
+
+fn nickname(row: &Row, index: usize) -> rusqlite::Result<Option<String>> {
+    Ok(row.get::<_, Option<String>>(index)?.filter(|nickname| !nickname.is_empty()))
+}
+
+fn read_inventory(conn: &Connection) -> Result<PaymentInventory, String> {
+    let cards = query(conn, "credit_cards", &["guid", "nickname", "expiration_month", "expiration_year", "name_on_card", "use_count", "use_date"], |row| {
+        Ok(PaymentCard {
+            guid: row.get(0)?,
+            nickname: nickname(row, 1)?,
+            expiration_month: row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+            expiration_year: row.get::<_, Option<i64>>(3)?.unwrap_or_default(),
+            has_billing_name: row.get::<_, Option<String>>(4)?.is_some_and(|name| !name.is_empty()),
+            use_count: row.get::<_, Option<i64>>(5)?.unwrap_or_default(),
+            last_used: timestamp(row, 6)?,
+        })
+    })?;
+
+    let address = |row: &Row| {
+        Ok(SavedAddress {
+            guid: row.get(0)?,
+            use_count: row.get::<_, Option<i64>>(1)?.unwrap_or_default(),
+            last_used: timestamp(row, 2)?,
+            date_modified: timestamp(row, 3)?,
+        })
+    };
+    let mut addresses = query(conn, "local_addresses", &["guid", "use_count", "use_date", "date_modified"], address)?;
+    addresses.extend(query(conn, "autofill_profiles", &["guid", "use_count", "use_date", "date_modified"], address)?);
+
+    let ibans = query(conn, "local_ibans", &["guid", "nickname", "use_count", "use_date"], |row| {
+        Ok(SavedIban {
+            guid: row.get(0)?,
+            nickname: nickname(row, 1)?,
+            use_count: row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+            last_used: timestamp(row, 3)?,
+        })
+    })?;
+
+    Ok(PaymentInventory { cards, addresses, ibans })
+}
+
+#[tauri::command]
+pub fn payment_data(browser_name: String, profile_name: String) -> Result<PaymentInventory, String> {
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    let working_copy = WorkingCopy::new(&web_data_path(&location)?)?;
+    let conn = working_copy.open()?;
+    read_inventory(&conn)
+}
+
+/// Deletes saved cards, addresses and IBANs by `guid`, including their rows
+/// in related tables. `rows_deleted` counts the items removed.
+#[tauri::command]
+pub fn delete_payment_data(browser_name: String, profile_name: String, items: Vec<PaymentDataRef>) -> Result<DeletionReport, String> {
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    let web_data = web_data_path(&location)?;
+    ensure_browser_closed(&location.browser)?;
+
+    let working_copy = WorkingCopy::new(&web_data)?;
+    let items_deleted = {
+        let mut conn = working_copy.open()?;
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut items_deleted = 0;
+        for item in &items {
+            let mut rows_affected = 0;
+            for table in tables(item.kind) {
+                if columns(&tx, table)?.iter().any(|c| c == "guid") {
+                    rows_affected += tx
+                        .execute(&format!("DELETE FROM {} WHERE guid = ?1", table), [&item.guid])
+                        .map_err(|e| format!("Failed to delete from {}: {}", table, e))?;
+                }
+            }
+            if rows_affected > 0 {
+                items_deleted += 1;
+            }
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        items_deleted
+    }; // conn is dropped here, closing the file handle
+    println!("Successfully deleted {} payment data items from profile: {}", items_deleted, location.profile);
+
+    Ok(DeletionReport {
+        rows_deleted: items_deleted,
+        secure_deletion: working_copy.write_back_deleted(&web_data)?,
+    })
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A saved payment card. The encrypted card number is never read.
+#[derive(Serialize)]
+pub struct PaymentCard {
+    pub guid: String,
+    pub nickname: Option<String>,
+    pub expiration_month: i64,
+    pub expiration_year: i64,
+    pub has_billing_name: bool,
+    pub use_count: i64,
+    pub last_used: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct SavedAddress {
+    pub guid: String,
+    pub use_count: i64,
+    pub last_used: DateTime<Utc>,
+    pub date_modified: DateTime<Utc>,
+}
+
+/// A saved IBAN. The encrypted value is never read.
+#[derive(Serialize)]
+pub struct SavedIban {
+    pub guid: String,
+    pub nickname: Option<String>,
+    pub use_count: i64,
+    pub last_used: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct PaymentInventory {
+    pub cards: Vec<PaymentCard>,
+    pub addresses: Vec<SavedAddress>,
+    pub ibans: Vec<SavedIban>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentDataKind {
+    Card,
+    Address,
+    Iban,
+}
+
+#[derive(Deserialize)]
+pub struct PaymentDataRef {
+    pub kind: PaymentDataKind,
+    pub guid: String,
+}
@@ -0,0 +1,161 @@
+use chrono::{Duration, Utc};
+use tauri::State;
+use uuid::Uuid;
+
+pub mod types;
+
+use crate::{
+    browsers::{all_profiles, ensure_browser_closed, locate_profile, ProfileLocation},
+    database::{SecureDeletion, WorkingCopy},
+    logins::{delete_entries, select_entries, LoginFilter},
+};
+use types::{DeletionPlan, ExecutionReport, PlanKind, PlanState, PlanTarget, SkippedProfile, TargetResult};
+
+/// How long a plan can be executed after it was issued.
+const PLAN_TTL_MINUTES: i64 = 10;
+
+pub fn plan_target(location: &ProfileLocation, filter: LoginFilter) -> Result<PlanTarget, String> {
+    let working_copy = WorkingCopy::new(&location.login_data)?;
+    let conn = working_copy.open()?;
+    let entries = select_entries(&conn, &filter)?;
+
+    Ok(PlanTarget {
+        browser: location.browser.clone(),
+        profile: location.profile.clone(),
+        database: location.login_data.clone(),
+        count: entries.len(),
+        entries,
+        filter,
+    })
+}
+
+pub fn issue_plan(kind: PlanKind, targets: Vec<PlanTarget>, skipped: Vec<SkippedProfile>, state: &PlanState) -> DeletionPlan {
+    let created_at = Utc::now();
+    let plan = DeletionPlan {
+        id: Uuid::new_v4().to_string(),
+        kind,
+        created_at,
+        expires_at: created_at + Duration::minutes(PLAN_TTL_MINUTES),
+        total: targets.iter().map(|t| t.count).sum(),
+        targets,
+        skipped,
+    };
+
+    let mut plans = state.plans.lock().unwrap();
+    plans.retain(|_, p| p.expires_at > created_at);
+    plans.insert(plan.id.clone(), plan.clone());
+    plan
+}
+
+#[tauri::command]
+pub fn plan_delete_all_passwords(state: State<PlanState>) -> Result<DeletionPlan, String> {
+    let mut targets = Vec::new();
+    let mut skipped = Vec::new();
+    for location in all_profiles()? {
+        match plan_target(&location, LoginFilter::All) {
+            Ok(target) => targets.push(target),
+            Err(error) => skipped.push(SkippedProfile {
+                browser: location.browser,
+                profile: location.profile,
+                error,
+            }),
+        }
+    }
+    Ok(issue_plan(PlanKind::AllPasswords, targets, skipped, &state))
+}
+
+#[tauri::command]
+pub fn plan_delete_profile(browser_name: String, profile_name: String, state: State<PlanState>) -> Result<DeletionPlan, String> {
+    let location = locate_profile(&browser_name, &profile_name)?;
+    let target = plan_target(&location, LoginFilter::All)?;
+    let kind = PlanKind::Profile {
+        browser: location.browser,
+        profile: location.profile,
+    };
+    Ok(issue_plan(kind, vec![target], Vec::new(), &state))
+}
+
+#[tauri::command]
+pub fn plan_delete_account_entry(browser_name: String, profile_name: String, url: String, username: String, state: State<PlanState>) -> Result<DeletionPlan, String> {
+    let location = locate_profile(&browser_name, &profile_name)?;
+    let filter = LoginFilter::Entry {
+        url: url.clone(),
+        username: username.clone(),
+    };
+    let target = plan_target(&location, filter)?;
+    let kind = PlanKind::AccountEntry {
+        browser: location.browser,
+        profile: location.profile,
+        url,
+        username,
+    };
+    Ok(issue_plan(kind, vec![target], Vec::new(), &state))
+}
+
+fn apply_target(target: &PlanTarget, working_copy: &WorkingCopy) -> Result<(usize, SecureDeletion), String> {
+    let rows_deleted = {
+        let mut conn = working_copy.open()?;
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let rows_deleted = delete_entries(&tx, &target.entries)?;
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        rows_deleted
+    }; // conn is dropped here, closing the file handle
+
+    let secure_deletion = working_copy.write_back_deleted(&target.database)?;
+    Ok((rows_deleted, secure_deletion))
+}
+
+/// Applies a plan issued by one of the `plan_*` commands. Plans are single
+/// use; every target is re-read and compared with the plan before any
+/// database is modified, so a stale plan changes nothing. Once the targets
+/// are applied, a target that fails does not stop the others; the report
+/// lists each target's outcome.
+#[tauri::command]
+pub fn execute_plan(plan_id: String, state: State<PlanState>) -> Result<ExecutionReport, String> {
+    let plan = state.plans.lock().unwrap().remove(&plan_id).ok_or_else(|| format!("Plan not found: {}", plan_id))?;
+    if Utc::now() >= plan.expires_at {
+        return Err(format!("Plan {} expired at {}", plan.id, plan.expires_at));
+    }
+
+    let mut prepared = Vec::new();
+    for target in &plan.targets {
+        ensure_browser_closed(&target.browser)?;
+        let working_copy = WorkingCopy::new(&target.database)?;
+        {
+            let conn = working_copy.open()?;
+            if select_entries(&conn, &target.filter)? != target.entries {
+                return Err(format!("Login Data for profile '{}' in {} changed since plan {} was created", target.profile, target.browser, plan.id));
+            }
+        }
+        prepared.push((target, working_copy));
+    }
+
+    let mut results = Vec::new();
+    for (target, working_copy) in prepared {
+        if target.entries.is_empty() {
+            continue;
+        }
+
+        let (rows_deleted, secure_deletion, error) = match apply_target(target, &working_copy) {
+            Ok((rows_deleted, secure_deletion)) => (rows_deleted, Some(secure_deletion), None),
+            Err(e) => {
+                eprintln!("Failed to apply plan {} to profile '{}' in {}: {}", plan.id, target.profile, target.browser, e);
+                (0, None, Some(e))
+            }
+        };
+        results.push(TargetResult {
+            browser: target.browser.clone(),
+            profile: target.profile.clone(),
+            database: target.database.clone(),
+            rows_deleted,
+            secure_deletion,
+            error,
+        });
+    }
+
+    Ok(ExecutionReport {
+        plan_id: plan.id,
+        total_deleted: results.iter().map(|r| r.rows_deleted).sum(),
+        targets: results,
+    })
+}
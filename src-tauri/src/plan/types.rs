@@ -0,0 +1,74 @@
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    cleanup::types::CleanupRule,
+    database::SecureDeletion,
+    logins::{LoginEntry, LoginFilter},
+};
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlanKind {
+    AllPasswords,
+    Profile { browser: String, profile: String },
+    AccountEntry { browser: String, profile: String, url: String, username: String },
+    Cleanup { rule: CleanupRule },
+}
+
+#[derive(Serialize, Clone)]
+pub struct PlanTarget {
+    pub browser: String,
+    pub profile: String,
+    pub database: PathBuf,
+    pub count: usize,
+    pub entries: Vec<LoginEntry>,
+    #[serde(skip)]
+    pub filter: LoginFilter,
+}
+
+/// A profile left out of a plan because its `Login Data` could not be read.
+#[derive(Serialize, Clone)]
+pub struct SkippedProfile {
+    pub browser: String,
+    pub profile: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DeletionPlan {
+    pub id: String,
+    pub kind: PlanKind,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub total: usize,
+    pub targets: Vec<PlanTarget>,
+    /// Profiles the plan does not cover; executing it leaves them untouched.
+    pub skipped: Vec<SkippedProfile>,
+}
+
+#[derive(Serialize)]
+pub struct TargetResult {
+    pub browser: String,
+    pub profile: String,
+    pub database: PathBuf,
+    pub rows_deleted: usize,
+    /// `None` when the target failed.
+    pub secure_deletion: Option<SecureDeletion>,
+    /// Why the target was left unchanged.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ExecutionReport {
+    pub plan_id: String,
+    pub total_deleted: usize,
+    pub targets: Vec<TargetResult>,
+}
+
+#[derive(Default)]
+pub struct PlanState {
+    pub plans: Mutex<HashMap<String, DeletionPlan>>,
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+pub mod types;
+
+use crate::database::replace_file;
+use types::{PolicyInstallResult, PolicySettings};
+
+const POLICY_FILE_NAME: &str = "totlaunloc.json";
+
+/// Managed policy directories of Chromium-based browsers on Linux, relative
+/// to the filesystem root.
+const POLICY_DIRS: [(&str, &str); 4] = [
+    ("Google Chrome", "etc/opt/chrome/policies/managed"),
+    ("Chromium", "etc/chromium/policies/managed"),
+    ("Brave Browser", "etc/brave/policies/managed"),
+    ("Microsoft Edge", "etc/opt/edge/policies/managed"),
+];
+
+/// `target_root` defaults to `/` and is only optional on Linux, where the
+/// browsers read these directories.
+fn policy_targets(target_root: Option<String>, browsers: &[String]) -> Result<Vec<(String, PathBuf)>, String> {
+    let root = match target_root {
+        Some(root) => PathBuf::from(root),
+        None if cfg!(target_os = "linux") => PathBuf::from("/"),
+        None => return Err("Managed policies are only read from these directories on Linux; pass a target root".to_string()),
+    };
+
+    for browser in browsers {
+        if !POLICY_DIRS.iter().any(|(name, _)| name == browser) {
+            return Err(format!("No managed policy directory known for {}", browser));
+        }
+    }
+
+    Ok(POLICY_DIRS
+        .iter()
+        .filter(|(name, _)| browsers.is_empty() || browsers.iter().any(|b| b == name))
+        .map(|(name, dir)| (name.to_string(), root.join(dir).join(POLICY_FILE_NAME)))
+        .collect())
+}
+
+/// Browsers run as regular users, so a newly created policy file must be
+/// world-readable.
+#[cfg(unix)]
+fn make_readable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o644))
+}
+
+#[cfg(not(unix))]
+fn make_readable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn install_policy(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let dir = path.parent().ok_or_else(|| format!("Invalid policy path: {:?}", path))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    replace_file(path, |file| file.write_all(contents))?;
+    make_readable(path).map_err(|e| format!("Failed to set permissions of {}: {}", path.display(), e))
+}
+
+/// Writes the managed policy file for each selected browser (all known ones
+/// when `browsers` is empty). Writing under `/etc` requires root.
+#[tauri::command]
+pub fn install_managed_policies(settings: PolicySettings, target_root: Option<String>, browsers: Vec<String>) -> Result<Vec<PolicyInstallResult>, String> {
+    let contents = serde_json::to_vec_pretty(&settings).map_err(|e| format!("Failed to serialize policies: {}", e))?;
+    if contents == b"{}" {
+        return Err("No policies given".to_string());
+    }
+
+    Ok(policy_targets(target_root, &browsers)?
+        .into_iter()
+        .map(|(browser, path)| PolicyInstallResult {
+            error: install_policy(&path, &contents).err(),
+            browser,
+            path,
+        })
+        .collect())
+}
+
+/// Removes the policy files written by `install_managed_policies`.
+#[tauri::command]
+pub fn remove_managed_policies(target_root: Option<String>, browsers: Vec<String>) -> Result<Vec<PolicyInstallResult>, String> {
+    Ok(policy_targets(target_root, &browsers)?
+        .into_iter()
+        .filter(|(_, path)| path.exists())
+        .map(|(browser, path)| PolicyInstallResult {
+            error: fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e)).err(),
+            browser,
+            path,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> PolicySettings {
+        PolicySettings {
+            password_manager_enabled: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn installs_and_removes_policy_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Some(dir.path().display().to_string());
+        let path = dir.path().join("etc/chromium/policies/managed").join(POLICY_FILE_NAME);
+
+        let results = install_managed_policies(settings(), root.clone(), vec!["Chromium".to_string()]).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, path);
+        assert!(results[0].error.is_none());
+
+        let written: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, serde_json::json!({ "PasswordManagerEnabled": false }));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o644);
+        }
+
+        let removed = remove_managed_policies(root.clone(), Vec::new()).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert!(removed[0].error.is_none());
+        assert!(!path.exists());
+        assert!(remove_managed_policies(root, Vec::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_browsers_and_empty_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Some(dir.path().display().to_string());
+
+        let error = install_managed_policies(settings(), root.clone(), vec!["Firefox".to_string()]).err().unwrap();
+        assert_eq!(error, "No managed policy directory known for Firefox");
+        let error = remove_managed_policies(root.clone(), vec!["Firefox".to_string()]).err().unwrap();
+        assert_eq!(error, "No managed policy directory known for Firefox");
+
+        let error = install_managed_policies(PolicySettings::default(), root, Vec::new()).err().unwrap();
+        assert_eq!(error, "No policies given");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Chromium enterprise policies written to the managed policy file. Unset
+/// policies are left out so the browser default applies.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct PolicySettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_manager_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_leak_detection_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_sharing_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autofill_address_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autofill_credit_card_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import_saved_passwords: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct PolicyInstallResult {
+    pub browser: String,
+    pub path: PathBuf,
+    pub error: Option<String>,
+}
@@ -0,0 +1,172 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    browsers::{browser_path, ensure_browser_closed, find_browser, read_local_state, resolve_profile},
+    database::replace_file,
+};
+
+/// Snapshots kept per browser; older ones are deleted after each removal.
+const SNAPSHOTS_KEPT: usize = 5;
+
+#[derive(Serialize)]
+pub struct ProfileRemoval {
+    pub browser: String,
+    pub profile: String,
+    pub profile_dir: String,
+    /// Copy of the profile directory and `Local State` taken before removal.
+    pub snapshot: String,
+    /// Directory holding this browser's snapshots. Only the latest
+    /// [`SNAPSHOTS_KEPT`] are kept.
+    pub snapshots_dir: String,
+    /// Older snapshots deleted to stay within the limit.
+    pub snapshots_pruned: Vec<String>,
+    /// The profile the browser will open next, when the removed one was the
+    /// last used.
+    pub last_used: Option<String>,
+}
+
+fn copy_dir(source: &Path, target: &Path) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let destination = target.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &destination)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &destination)?;
+        }
+        // Symlinks (such as lock files) are not part of the profile's data.
+    }
+    Ok(())
+}
+
+/// Deletes all but the newest `keep` snapshots in `snapshots_dir`, returning
+/// the deleted paths. Failures are logged, since the removal itself succeeded.
+fn prune_snapshots(snapshots_dir: &Path, keep: usize) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(snapshots_dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    snapshots.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    let mut pruned = Vec::new();
+    for (_, path) in snapshots.into_iter().skip(keep) {
+        match fs::remove_dir_all(&path) {
+            Ok(_) => pruned.push(path.display().to_string()),
+            Err(e) => eprintln!("Failed to delete old snapshot {}: {}", path.display(), e),
+        }
+    }
+    pruned
+}
+
+/// Drops `profile_dir` from `profile.info_cache`, `profile.profiles_order`
+/// and `profile.last_active_profiles`, moving `profile.last_used` to the
+/// first remaining profile if it pointed at the removed one.
+fn unlink_profile(local_state: &mut serde_json::Value, profile_dir: &str) -> Result<Option<String>, String> {
+    let profile = local_state["profile"].as_object_mut().ok_or("Local State has no profile section")?;
+
+    if let Some(info_cache) = profile.get_mut("info_cache").and_then(|v| v.as_object_mut()) {
+        info_cache.remove(profile_dir);
+    }
+    for key in ["profiles_order", "last_active_profiles"] {
+        if let Some(list) = profile.get_mut(key).and_then(|v| v.as_array_mut()) {
+            list.retain(|dir| dir.as_str() != Some(profile_dir));
+        }
+    }
+
+    if profile.get("last_used").and_then(|v| v.as_str()) != Some(profile_dir) {
+        return Ok(None);
+    }
+
+    let next = profile
+        .get("profiles_order")
+        .and_then(|v| v.as_array())
+        .and_then(|order| order.first())
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .or_else(|| profile.get("info_cache").and_then(|v| v.as_object()).and_then(|cache| cache.keys().next().cloned()));
+
+    match &next {
+        Some(dir) => profile.insert("last_used".to_string(), dir.clone().into()),
+        None => profile.remove("last_used"),
+    };
+    Ok(next)
+}
+
+/// Removes a whole profile: its directory and its entries in `Local State`.
+///
+/// The browser must be closed. The profile directory and `Local State` are
+/// first copied to `snapshots/<browser>` in the app data directory, where the
+/// latest [`SNAPSHOTS_KEPT`] snapshots are kept. The directory is then moved
+/// aside, `Local State` rewritten and the moved directory deleted, so a
+/// failure before the rewrite puts the directory back.
+#[tauri::command]
+pub fn remove_profile(app: AppHandle, browser_name: String, profile_name: String) -> Result<ProfileRemoval, String> {
+    let browser = find_browser(&browser_name)?;
+    if browser.name == "Opera GX" {
+        return Err("Opera GX keeps all of its profiles in one directory; its profiles cannot be removed individually".to_string());
+    }
+
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    ensure_browser_closed(&location.browser)?;
+
+    let browser_path = browser_path(&browser);
+    let profile_dir = location
+        .profile_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid profile path: {:?}", location.profile_path))?;
+
+    let mut local_state = read_local_state(&browser_path)?;
+
+    let snapshots_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("snapshots")
+        .join(&browser.name);
+    let snapshot = snapshots_dir.join(format!("{}-{}", profile_dir, Local::now().format("%Y%m%d-%H%M%S")));
+    copy_dir(&location.profile_path, &snapshot.join(&profile_dir)).map_err(|e| format!("Failed to snapshot profile to {}: {}", snapshot.display(), e))?;
+    fs::copy(browser_path.join("Local State"), snapshot.join("Local State")).map_err(|e| format!("Failed to snapshot Local State: {}", e))?;
+
+    let last_used = unlink_profile(&mut local_state, &profile_dir)?;
+    let contents = serde_json::to_vec(&local_state).map_err(|e| format!("Failed to serialize Local State: {}", e))?;
+
+    let removed: PathBuf = browser_path.join(format!(".totlaunloc-removed-{}", profile_dir));
+    fs::rename(&location.profile_path, &removed).map_err(|e| format!("Failed to move profile directory aside: {}", e))?;
+
+    if let Err(e) = replace_file(&browser_path.join("Local State"), |file| io::Write::write_all(file, &contents)) {
+        return match fs::rename(&removed, &location.profile_path) {
+            Ok(_) => Err(e),
+            Err(restore) => Err(format!("{}; profile directory was left at {} ({})", e, removed.display(), restore)),
+        };
+    }
+
+    fs::remove_dir_all(&removed).map_err(|e| format!("Removed profile '{}' from Local State but failed to delete {}: {}", location.profile, removed.display(), e))?;
+
+    let snapshots_pruned = prune_snapshots(&snapshots_dir, SNAPSHOTS_KEPT);
+
+    println!("Removed profile '{}' from {}; snapshot at {}", location.profile, location.browser, snapshot.display());
+    Ok(ProfileRemoval {
+        browser: location.browser,
+        profile: location.profile,
+        profile_dir,
+        snapshot: snapshot.display().to_string(),
+        snapshots_dir: snapshots_dir.display().to_string(),
+        snapshots_pruned,
+        last_used,
+    })
+}
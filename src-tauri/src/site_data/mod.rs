@@ -0,0 +1,152 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::params_from_iter;
+
+pub mod types;
+
+use crate::{
+    browsers::{ensure_browser_closed, installed_profiles, resolve_profile, ProfileLocation},
+    database::{SecureDeletion, WorkingCopy},
+};
+use types::{CookieHost, SiteDataOutcome};
+
+/// Chromium moved `Cookies` into the `Network` directory in version 96.
+fn cookies_path(location: &ProfileLocation) -> Option<PathBuf> {
+    [location.profile_path.join("Network").join("Cookies"), location.profile_path.join("Cookies")]
+        .into_iter()
+        .find(|path| path.exists())
+}
+
+/// Whether `host` is `domain` or one of its subdomains. Cookie hosts may
+/// carry a leading dot.
+fn matches_domain(host: &str, domains: &[String]) -> bool {
+    let host = host.trim_start_matches('.').to_lowercase();
+    domains.iter().any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+}
+
+/// Extracts the host from per-origin storage names such as
+/// `https_www.example.com_0.indexeddb.leveldb`.
+fn storage_host(file_name: &str) -> Option<&str> {
+    let origin = [".indexeddb.leveldb", ".indexeddb.blob", ".localstorage-journal", ".localstorage"]
+        .iter()
+        .find_map(|suffix| file_name.strip_suffix(suffix))?;
+    let (_scheme, host_port) = origin.split_once('_')?;
+    host_port.rsplit_once('_').map(|(host, _port)| host)
+}
+
+fn delete_cookies(location: &ProfileLocation, domains: &[String]) -> Result<(usize, SecureDeletion), String> {
+    let Some(cookies) = cookies_path(location) else {
+        return Ok((0, SecureDeletion::default()));
+    };
+
+    let working_copy = WorkingCopy::new(&cookies)?;
+    let rows_affected = {
+        let conn = working_copy.open()?;
+        let mut stmt = conn.prepare("SELECT DISTINCT host_key FROM cookies").map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+        let hosts = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to execute SQL statement: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read cookie hosts: {}", e))?;
+
+        let matching: Vec<String> = hosts.into_iter().filter(|host| matches_domain(host, domains)).collect();
+        if matching.is_empty() {
+            return Ok((0, SecureDeletion::default()));
+        }
+
+        let sql = format!("DELETE FROM cookies WHERE host_key IN ({})", vec!["?"; matching.len()].join(", "));
+        conn.execute(&sql, params_from_iter(matching)).map_err(|e| format!("Failed to delete cookies: {}", e))?
+    }; // conn is dropped here, closing the file handle
+
+    let secure_deletion = working_copy.write_back_deleted(&cookies)?;
+    Ok((rows_affected, secure_deletion))
+}
+
+fn delete_storage(profile_path: &Path, domains: &[String]) -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for dir in ["IndexedDB", "Local Storage"] {
+        let Ok(entries) = fs::read_dir(profile_path.join(dir)) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !storage_host(&file_name).is_some_and(|host| matches_domain(host, domains)) {
+                continue;
+            }
+
+            let path = entry.path();
+            let result = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+            result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            removed.push(format!("{}/{}", dir, file_name));
+        }
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub fn cookie_hosts(browser_name: String, profile_name: String) -> Result<Vec<CookieHost>, String> {
+    let location = resolve_profile(&browser_name, &profile_name)?;
+    let cookies = cookies_path(&location).ok_or_else(|| format!("Cookies file does not exist in {:?}", location.profile_path))?;
+
+    let working_copy = WorkingCopy::new(&cookies)?;
+    let conn = working_copy.open()?;
+    let mut stmt = conn
+        .prepare("SELECT host_key, COUNT(*) FROM cookies GROUP BY host_key ORDER BY host_key")
+        .map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let hosts = stmt
+        .query_map([], |row| Ok(CookieHost { host: row.get(0)?, count: row.get(1)? }))
+        .map_err(|e| format!("Failed to execute SQL statement: {}", e))?;
+
+    hosts.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read cookie hosts: {}", e))
+}
+
+/// Deletes cookies and per-origin IndexedDB data for `domains` and their
+/// subdomains, in every profile unless `browsers`/`profiles` narrow it down.
+///
+/// Current Chromium keeps Local Storage for all sites in one LevelDB
+/// database, which this command cannot edit; only the per-origin files of
+/// older versions are removed, and `local_storage_kept` flags profiles whose
+/// Local Storage may still hold data for the domains.
+#[tauri::command]
+pub fn delete_site_data(domains: Vec<String>, browsers: Vec<String>, profiles: Vec<String>) -> Result<Vec<SiteDataOutcome>, String> {
+    let domains: Vec<String> = domains.iter().map(|d| d.trim().trim_start_matches('.').to_lowercase()).filter(|d| !d.is_empty()).collect();
+    if domains.is_empty() {
+        return Err("No domains given".to_string());
+    }
+
+    let mut outcomes = Vec::new();
+    for location in installed_profiles()? {
+        if (!browsers.is_empty() && !browsers.contains(&location.browser)) || (!profiles.is_empty() && !profiles.contains(&location.profile)) {
+            continue;
+        }
+
+        let mut outcome = SiteDataOutcome {
+            browser: location.browser.clone(),
+            profile: location.profile.clone(),
+            cookies_deleted: 0,
+            storage_removed: Vec::new(),
+            secure_deletion: SecureDeletion::default(),
+            local_storage_kept: location.profile_path.join("Local Storage").join("leveldb").exists(),
+            error: None,
+        };
+
+        let result = ensure_browser_closed(&location.browser)
+            .and_then(|_| delete_cookies(&location, &domains))
+            .and_then(|(cookies_deleted, secure_deletion)| {
+                outcome.cookies_deleted = cookies_deleted;
+                outcome.secure_deletion = secure_deletion;
+                delete_storage(&location.profile_path, &domains)
+            });
+        match result {
+            Ok(removed) => outcome.storage_removed = removed,
+            Err(e) => outcome.error = Some(e),
+        }
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
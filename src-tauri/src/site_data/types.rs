@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use crate::database::SecureDeletion;
+
+#[derive(Serialize)]
+pub struct CookieHost {
+    pub host: String,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct SiteDataOutcome {
+    pub browser: String,
+    pub profile: String,
+    pub cookies_deleted: usize,
+    /// IndexedDB and legacy Local Storage entries removed from disk.
+    pub storage_removed: Vec<String>,
+    pub secure_deletion: SecureDeletion,
+    /// The profile's LevelDB Local Storage was not cleared.
+    pub local_storage_kept: bool,
+    pub error: Option<String>,
+}
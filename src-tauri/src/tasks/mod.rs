@@ -0,0 +1,132 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+pub mod types;
+
+use types::{TaskFinished, TaskProgress, TaskState, TaskStatus};
+
+/// Error reported for work skipped or abandoned because its task was
+/// cancelled.
+pub const CANCELLED: &str = "Cancelled";
+
+/// Handle passed to long-running operations to report progress and check
+/// for cancellation. Synchronous commands use a detached task, which emits
+/// nothing and is never cancelled.
+pub struct Task {
+    id: String,
+    app: Option<AppHandle>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Task {
+    pub fn detached() -> Self {
+        Task {
+            id: String::new(),
+            app: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with [`CANCELLED`] once the task has been cancelled. Called
+    /// before writing a database back, so a cancelled task leaves the
+    /// database it was working on untouched.
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn progress(&self, browser: &str, profile: &str, processed: usize, total: usize) {
+        if let Some(app) = &self.app {
+            let payload = TaskProgress {
+                task_id: self.id.clone(),
+                browser: browser.to_string(),
+                profile: profile.to_string(),
+                processed,
+                total,
+            };
+            if let Err(e) = app.emit("task-progress", payload) {
+                eprintln!("Failed to emit progress for task {}: {}", self.id, e);
+            }
+        }
+    }
+}
+
+/// Runs `operation` on a background thread and returns its task id. Progress
+/// is reported through `task-progress` events and the outcome through a
+/// `task-finished` event, also when `operation` panics.
+pub fn spawn<T, F>(app: AppHandle, state: &TaskState, operation: F) -> String
+where
+    T: Serialize,
+    F: FnOnce(&Task) -> Result<T, String> + Send + 'static,
+{
+    let id = Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state.tasks.lock().unwrap().insert(id.clone(), cancelled.clone());
+
+    let tasks = state.tasks.clone();
+    let task = Task {
+        id: id.clone(),
+        app: Some(app.clone()),
+        cancelled,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let outcome = catch_unwind(AssertUnwindSafe(|| operation(&task)))
+            .unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Err(format!("Task failed unexpectedly: {}", message))
+            })
+            .and_then(|result| serde_json::to_value(result).map_err(|e| format!("Failed to serialize task result: {}", e)));
+        tasks.lock().unwrap().remove(&task.id);
+
+        let payload = match outcome {
+            Ok(result) => TaskFinished {
+                task_id: task.id.clone(),
+                status: if task.is_cancelled() { TaskStatus::Cancelled } else { TaskStatus::Completed },
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => TaskFinished {
+                task_id: task.id.clone(),
+                status: if task.is_cancelled() { TaskStatus::Cancelled } else { TaskStatus::Failed },
+                result: None,
+                error: Some(error),
+            },
+        };
+        if let Err(e) = app.emit("task-finished", payload) {
+            eprintln!("Failed to emit completion of task {}: {}", task.id, e);
+        }
+    });
+
+    id
+}
+
+/// Asks a running task to stop. It finishes the profile it is on without
+/// writing that profile's database back.
+#[tauri::command]
+pub fn cancel_task(task_id: String, state: State<TaskState>) -> Result<(), String> {
+    let tasks = state.tasks.lock().unwrap();
+    let cancelled = tasks.get(&task_id).ok_or_else(|| format!("No running task with id {}", task_id))?;
+    cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
+
+use serde::Serialize;
+
+/// Cancellation flags of the tasks currently running, by task id.
+#[derive(Default)]
+pub struct TaskState {
+    pub tasks: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+/// Payload of the `task-progress` event, emitted before each profile is
+/// processed.
+#[derive(Serialize, Clone)]
+pub struct TaskProgress {
+    pub task_id: String,
+    pub browser: String,
+    pub profile: String,
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Payload of the `task-finished` event. A cancelled task still carries the
+/// result of the work done before it stopped.
+#[derive(Serialize, Clone)]
+pub struct TaskFinished {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,282 @@
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use totp_rs::Algorithm;
+use uuid::Uuid;
+
+use super::{aes_gcm_decrypt, aes_gcm_encrypt, candidate, require_password, BackupEntry, Secret};
+use crate::totp::{
+    algorithm_name,
+    import::ImportCandidate,
+    types::{Account, OtpKind},
+};
+
+/// Slot type of a key slot unlocked with a password.
+const PASSWORD_SLOT: u32 = 1;
+const FILE_VERSION: u32 = 1;
+const DB_VERSION: u32 = 2;
+/// scrypt parameters Aegis itself uses for password slots.
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// Upper bounds on the scrypt parameters read from a backup, eight times
+/// Aegis's cost (256 MiB). Larger ones only come from crafted files.
+const MAX_SCRYPT_N: u64 = 1 << 18;
+const MAX_SCRYPT_R: u32 = 8;
+const MAX_SCRYPT_P: u32 = 4;
+const TAG_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct AegisFile {
+    #[serde(default)]
+    version: u32,
+    header: AegisHeader,
+    db: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AegisHeader {
+    slots: Option<Vec<AegisSlot>>,
+    params: Option<AegisParams>,
+}
+
+/// A copy of the master key, encrypted with a key derived from the password
+/// by scrypt.
+#[derive(Serialize, Deserialize)]
+struct AegisSlot {
+    #[serde(rename = "type")]
+    slot_type: u32,
+    #[serde(default)]
+    uuid: String,
+    key: String,
+    key_params: AegisParams,
+    n: Option<u64>,
+    r: Option<u32>,
+    p: Option<u32>,
+    salt: Option<String>,
+    /// Set on slots written without the nonce reuse of early Aegis versions.
+    #[serde(default)]
+    repaired: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AegisParams {
+    nonce: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AegisDb {
+    #[serde(default)]
+    version: u32,
+    entries: Vec<AegisEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AegisEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    uuid: String,
+    name: String,
+    issuer: String,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    favorite: bool,
+    icon: Option<String>,
+    icon_mime: Option<String>,
+    info: AegisInfo,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AegisInfo {
+    secret: String,
+    algo: Option<String>,
+    digits: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    counter: Option<u64>,
+}
+
+fn hex_field(name: &str, value: &str) -> Result<Vec<u8>, String> {
+    hex::decode(value).map_err(|e| format!("Invalid {} in Aegis backup: {}", name, e))
+}
+
+fn with_tag(ciphertext: Vec<u8>, params: &AegisParams) -> Result<Vec<u8>, String> {
+    let mut data = ciphertext;
+    data.extend(hex_field("tag", &params.tag)?);
+    Ok(data)
+}
+
+fn unlock_slot(slot: &AegisSlot, password: &str) -> Result<Option<Vec<u8>>, String> {
+    let (Some(n), Some(r), Some(p), Some(salt)) = (slot.n, slot.r, slot.p, &slot.salt) else {
+        return Err("Aegis password slot is missing its scrypt parameters".to_string());
+    };
+    if n > MAX_SCRYPT_N || r > MAX_SCRYPT_R || p > MAX_SCRYPT_P {
+        return Err("Key derivation parameters of the backup exceed the supported limits".to_string());
+    }
+    if !n.is_power_of_two() {
+        return Err(format!("Invalid scrypt parameters in Aegis backup: N = {} is not a power of two", n));
+    }
+
+    let params = scrypt::Params::new(n.trailing_zeros() as u8, r, p, 32).map_err(|e| format!("Invalid scrypt parameters in Aegis backup: {}", e))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), &hex_field("salt", salt)?, &params, &mut key).map_err(|e| format!("Failed to derive key: {}", e))?;
+
+    let master_key = with_tag(hex_field("key", &slot.key)?, &slot.key_params)?;
+    Ok(aes_gcm_decrypt(&key, &hex_field("nonce", &slot.key_params.nonce)?, &master_key))
+}
+
+fn decrypt_db(file: &AegisFile, password: &str) -> Result<AegisDb, String> {
+    let params = file.header.params.as_ref().ok_or("Aegis backup is missing its encryption parameters")?;
+    let slots = file.header.slots.as_deref().unwrap_or_default();
+
+    let mut master_key = None;
+    for slot in slots.iter().filter(|slot| slot.slot_type == PASSWORD_SLOT) {
+        if let Some(key) = unlock_slot(slot, password)? {
+            master_key = Some(key);
+            break;
+        }
+    }
+    let master_key = master_key.ok_or("Incorrect password for the Aegis backup")?;
+
+    let db = file.db.as_str().ok_or("Encrypted Aegis backup has no encrypted database")?;
+    let db = STANDARD.decode(db).map_err(|e| format!("Invalid database in Aegis backup: {}", e))?;
+    let plaintext = aes_gcm_decrypt(&master_key, &hex_field("nonce", &params.nonce)?, &with_tag(db, params)?).ok_or("Failed to decrypt the Aegis database")?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse Aegis database: {}", e))
+}
+
+/// Reads an Aegis vault export, plain or encrypted with a password slot.
+pub fn parse(json: serde_json::Value, password: Option<&str>) -> Result<Vec<ImportCandidate>, String> {
+    let file: AegisFile = serde_json::from_value(json).map_err(|e| format!("Failed to parse Aegis backup: {}", e))?;
+
+    let db = if file.db.is_object() {
+        serde_json::from_value(file.db).map_err(|e| format!("Failed to parse Aegis database: {}", e))?
+    } else {
+        decrypt_db(&file, require_password(password)?)?
+    };
+
+    Ok(db
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let icon = match (entry.icon, entry.icon_mime) {
+                (Some(icon), Some(mime)) => Some(format!("data:{};base64,{}", mime, icon)),
+                _ => None,
+            };
+            candidate(BackupEntry {
+                kind: entry.kind,
+                issuer: entry.issuer,
+                name: entry.name,
+                secret: Secret::Base32(entry.info.secret),
+                algorithm: entry.info.algo,
+                digits: entry.info.digits,
+                period: entry.info.period,
+                counter: entry.info.counter,
+                icon,
+            })
+        })
+        .collect())
+}
+
+fn to_entry(account: &Account) -> AegisEntry {
+    let (kind, algo, period, counter) = match (account.kind, account.totp.algorithm) {
+        (_, Algorithm::Steam) => ("steam", "SHA1", Some(account.totp.step), None),
+        (OtpKind::Totp, algorithm) => ("totp", algorithm_name(algorithm), Some(account.totp.step), None),
+        (OtpKind::Hotp, algorithm) => ("hotp", algorithm_name(algorithm), None, Some(account.counter)),
+    };
+    // Only icons imported as `data:` URLs travel with the account.
+    let (icon, icon_mime) = match account.icon.strip_prefix("data:").and_then(|rest| rest.split_once(";base64,")) {
+        Some((mime, data)) => (Some(data.to_string()), Some(mime.to_string())),
+        None => (None, None),
+    };
+
+    AegisEntry {
+        kind: kind.to_string(),
+        uuid: Uuid::new_v4().to_string(),
+        name: account.username.clone(),
+        issuer: account.account.clone(),
+        note: String::new(),
+        favorite: false,
+        icon,
+        icon_mime,
+        info: AegisInfo {
+            secret: base32::encode(base32::Alphabet::RFC4648 { padding: false }, &account.totp.secret),
+            algo: Some(algo.to_string()),
+            digits: Some(account.totp.digits),
+            period,
+            counter,
+        },
+    }
+}
+
+/// Splits AES-GCM output into the ciphertext and the tag Aegis stores apart.
+fn params(nonce: &[u8], mut ciphertext: Vec<u8>) -> (Vec<u8>, AegisParams) {
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LEN);
+    (
+        ciphertext,
+        AegisParams {
+            nonce: hex::encode(nonce),
+            tag: hex::encode(tag),
+        },
+    )
+}
+
+/// Writes `accounts` as an Aegis vault export, encrypted under a password
+/// slot when `password` is given.
+pub fn export(accounts: &[Account], password: Option<&str>) -> Result<Vec<u8>, String> {
+    let db = AegisDb {
+        version: DB_VERSION,
+        entries: accounts.iter().map(to_entry).collect(),
+    };
+
+    let file = match password {
+        None => AegisFile {
+            version: FILE_VERSION,
+            header: AegisHeader { slots: None, params: None },
+            db: serde_json::to_value(&db).map_err(|e| format!("Failed to serialize Aegis database: {}", e))?,
+        },
+        Some(password) => {
+            let mut master_key = [0u8; 32];
+            OsRng.fill_bytes(&mut master_key);
+            let mut salt = [0u8; 32];
+            OsRng.fill_bytes(&mut salt);
+
+            let scrypt_params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, 32).map_err(|e| format!("Invalid scrypt parameters: {}", e))?;
+            let mut key = [0u8; 32];
+            scrypt::scrypt(password.as_bytes(), &salt, &scrypt_params, &mut key).map_err(|e| format!("Failed to derive key: {}", e))?;
+
+            let (nonce, encrypted_key) = aes_gcm_encrypt(&key, &master_key)?;
+            let (encrypted_key, key_params) = params(&nonce, encrypted_key);
+            let slot = AegisSlot {
+                slot_type: PASSWORD_SLOT,
+                uuid: Uuid::new_v4().to_string(),
+                key: hex::encode(encrypted_key),
+                key_params,
+                n: Some(1 << SCRYPT_LOG_N),
+                r: Some(SCRYPT_R),
+                p: Some(SCRYPT_P),
+                salt: Some(hex::encode(salt)),
+                repaired: true,
+            };
+
+            let plaintext = serde_json::to_vec(&db).map_err(|e| format!("Failed to serialize Aegis database: {}", e))?;
+            let (nonce, ciphertext) = aes_gcm_encrypt(&master_key, &plaintext)?;
+            let (ciphertext, db_params) = params(&nonce, ciphertext);
+
+            AegisFile {
+                version: FILE_VERSION,
+                header: AegisHeader {
+                    slots: Some(vec![slot]),
+                    params: Some(db_params),
+                },
+                db: STANDARD.encode(ciphertext).into(),
+            }
+        }
+    };
+
+    serde_json::to_vec_pretty(&file).map_err(|e| format!("Failed to serialize Aegis backup: {}", e))
+}
@@ -0,0 +1,73 @@
+use pbkdf2::pbkdf2_hmac;
+use serde::Deserialize;
+use sha1::Sha1;
+
+use super::{aes_gcm_decrypt, candidate, BackupEntry, Secret};
+use crate::totp::import::ImportCandidate;
+
+const ITERATIONS_LEN: usize = 4;
+const SALT_LEN: usize = 12;
+const IV_LEN: usize = 12;
+/// andOTP picks between 140000 and 160000 iterations. Counts far outside
+/// that mean the file is not an andOTP backup, and would stall the import.
+const ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 1_000..=1_000_000;
+
+#[derive(Deserialize)]
+struct AndOtpEntry {
+    secret: String,
+    #[serde(default)]
+    issuer: String,
+    #[serde(default)]
+    label: String,
+    digits: Option<usize>,
+    #[serde(rename = "type")]
+    kind: String,
+    algorithm: Option<String>,
+    period: Option<u64>,
+    counter: Option<u64>,
+}
+
+/// Reads a plain andOTP JSON backup.
+pub fn parse(json: serde_json::Value) -> Result<Vec<ImportCandidate>, String> {
+    let entries: Vec<AndOtpEntry> = serde_json::from_value(json).map_err(|e| format!("Failed to parse andOTP backup: {}", e))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            candidate(BackupEntry {
+                kind: entry.kind,
+                issuer: entry.issuer,
+                name: entry.label,
+                secret: Secret::Base32(entry.secret),
+                algorithm: entry.algorithm,
+                digits: entry.digits,
+                period: entry.period,
+                counter: entry.counter,
+                icon: None,
+            })
+        })
+        .collect())
+}
+
+/// Reads a password-encrypted andOTP backup: the PBKDF2-HMAC-SHA1 iteration
+/// count (big-endian `u32`), salt and IV, followed by the AES-GCM
+/// ciphertext of the plain JSON backup.
+pub fn parse_encrypted(contents: &[u8], password: &str) -> Result<Vec<ImportCandidate>, String> {
+    if contents.len() < ITERATIONS_LEN + SALT_LEN + IV_LEN {
+        return Err("Unrecognized backup format".to_string());
+    }
+    let (iterations, rest) = contents.split_at(ITERATIONS_LEN);
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (iv, ciphertext) = rest.split_at(IV_LEN);
+
+    let iterations = u32::from_be_bytes(iterations.try_into().unwrap_or_default());
+    if !ITERATIONS_RANGE.contains(&iterations) {
+        return Err("Unrecognized backup format".to_string());
+    }
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha1>(password.as_bytes(), salt, iterations, &mut key);
+    let plaintext = aes_gcm_decrypt(&key, iv, ciphertext).ok_or("Incorrect password, or not an andOTP backup")?;
+
+    let json = serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse decrypted andOTP backup: {}", e))?;
+    parse(json)
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+
+use super::{candidate, BackupEntry, Secret};
+use crate::totp::import::ImportCandidate;
+
+#[derive(Deserialize)]
+struct FreeOtpFile {
+    tokens: Vec<FreeOtpToken>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FreeOtpToken {
+    algo: Option<String>,
+    counter: Option<u64>,
+    digits: Option<usize>,
+    #[serde(default)]
+    issuer_ext: String,
+    #[serde(default)]
+    label: String,
+    period: Option<u64>,
+    /// Raw secret bytes, written as Java's signed bytes.
+    secret: Vec<i8>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Reads a FreeOTP+ JSON backup.
+pub fn parse(json: serde_json::Value) -> Result<Vec<ImportCandidate>, String> {
+    let file: FreeOtpFile = serde_json::from_value(json).map_err(|e| format!("Failed to parse FreeOTP+ backup: {}", e))?;
+
+    Ok(file
+        .tokens
+        .into_iter()
+        .map(|token| {
+            candidate(BackupEntry {
+                kind: token.kind,
+                issuer: token.issuer_ext,
+                name: token.label,
+                secret: Secret::Bytes(token.secret.into_iter().map(|b| b as u8).collect()),
+                algorithm: token.algo,
+                digits: token.digits,
+                period: token.period,
+                counter: token.counter,
+                icon: None,
+            })
+        })
+        .collect())
+}
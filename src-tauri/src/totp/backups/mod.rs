@@ -0,0 +1,420 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, OsRng},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tauri::State;
+use totp_rs::{Algorithm, TOTP};
+use uuid::Uuid;
+
+use super::{
+    algorithm_name, build_totp, decode_secret, icon_or_default,
+    hotp::HOTP_STEP,
+    import::{add_candidates, is_duplicate, uri_candidate, ImportCandidate, ImportOutcome},
+    store::Vault,
+    types::{Account, AppState, OtpKind},
+};
+
+pub mod aegis;
+pub mod andotp;
+pub mod freeotp;
+pub mod twofas;
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupFormat {
+    Aegis,
+    TwoFas,
+    AndOtp,
+    FreeOtpPlus,
+    UriList,
+    /// This app's own encrypted backups, written by `export_accounts`.
+    Totlaunloc,
+}
+
+pub enum Secret {
+    Base32(String),
+    Bytes(Vec<u8>),
+}
+
+/// An account as read from a backup, before validation.
+pub struct BackupEntry {
+    /// `totp`, `hotp` or `steam`, in any case; anything else is unsupported.
+    pub kind: String,
+    pub issuer: String,
+    pub name: String,
+    pub secret: Secret,
+    pub algorithm: Option<String>,
+    pub digits: Option<usize>,
+    pub period: Option<u64>,
+    pub counter: Option<u64>,
+    /// A `data:` URL, for backups that embed icons.
+    pub icon: Option<String>,
+}
+
+/// How long a preview can be committed after it was made. Previews hold
+/// decrypted secrets, so they are not kept around.
+const IMPORT_TTL_MINUTES: i64 = 10;
+
+/// Backups parsed by `preview_import` and waiting for `commit_import`.
+pub struct PendingImport {
+    pub candidates: Vec<ImportCandidate>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PreviewStatus {
+    New,
+    Duplicate,
+    Unsupported { reason: String },
+}
+
+#[derive(Serialize)]
+pub struct PreviewEntry {
+    pub index: usize,
+    pub account: String,
+    pub username: String,
+    pub kind: Option<OtpKind>,
+    pub algorithm: Option<String>,
+    pub digits: Option<usize>,
+    pub period: Option<u64>,
+    #[serde(flatten)]
+    pub status: PreviewStatus,
+}
+
+#[derive(Serialize)]
+pub struct ImportPreview {
+    pub import_id: String,
+    pub format: BackupFormat,
+    pub entries: Vec<PreviewEntry>,
+}
+
+/// Decrypts AES-256-GCM `ciphertext`, which ends with the 16-byte tag.
+/// Fails without detail, since a wrong key and a tampered file look the same.
+pub fn aes_gcm_decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    if nonce.len() != 12 {
+        return None;
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).ok()?;
+    cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()
+}
+
+/// Encrypts with AES-256-GCM under a fresh nonce, returned with the
+/// ciphertext.
+pub fn aes_gcm_encrypt(key: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("Invalid key: {}", e))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|e| format!("Failed to encrypt: {}", e))?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+pub fn require_password(password: Option<&str>) -> Result<&str, String> {
+    password.filter(|p| !p.is_empty()).ok_or_else(|| "This backup is encrypted; enter its password".to_string())
+}
+
+fn algorithm(name: Option<&str>) -> Result<Algorithm, String> {
+    match name.map(str::to_uppercase).as_deref() {
+        None | Some("SHA1") => Ok(Algorithm::SHA1),
+        Some("SHA256") => Ok(Algorithm::SHA256),
+        Some("SHA512") => Ok(Algorithm::SHA512),
+        Some(other) => Err(format!("Unsupported algorithm: {}", other)),
+    }
+}
+
+fn to_account(entry: &BackupEntry) -> Result<Account, String> {
+    let secret = match &entry.secret {
+        Secret::Base32(secret) => decode_secret(secret).map_err(|e| e.to_string())?,
+        Secret::Bytes(bytes) => bytes.clone(),
+    };
+    let digits = entry.digits.unwrap_or(6);
+
+    let mut account = match entry.kind.to_lowercase().as_str() {
+        "totp" => {
+            let totp = build_totp(algorithm(entry.algorithm.as_deref())?, digits, entry.period.unwrap_or(30), secret)?;
+            Account::new(entry.issuer.clone(), entry.name.clone(), totp, OtpKind::Totp, 0)
+        }
+        "hotp" => {
+            let totp = build_totp(algorithm(entry.algorithm.as_deref())?, digits, HOTP_STEP, secret)?;
+            Account::new(entry.issuer.clone(), entry.name.clone(), totp, OtpKind::Hotp, entry.counter.unwrap_or(0))
+        }
+        "steam" => {
+            let issuer = if entry.issuer.is_empty() { "Steam".to_string() } else { entry.issuer.clone() };
+            Account::new(issuer, entry.name.clone(), TOTP::new_steam(secret), OtpKind::Totp, 0)
+        }
+        other => return Err(format!("Unsupported token type: {}", other)),
+    };
+
+    if let Some(icon) = &entry.icon {
+        account.icon = icon_or_default(icon.clone());
+    }
+    Ok(account)
+}
+
+pub fn candidate(entry: BackupEntry) -> ImportCandidate {
+    ImportCandidate {
+        parsed: to_account(&entry),
+        account: entry.issuer,
+        username: entry.name,
+    }
+}
+
+/// Plain text with one `otpauth://` or `steam://` URL per line, as exported
+/// by FreeOTP+ and several other apps.
+fn parse_uri_list(text: &str) -> Vec<ImportCandidate> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(uri_candidate)
+        .collect()
+}
+
+/// A backup in the format of the app's own store, keeping icons as they were.
+fn parse_native(contents: &[u8], password: &str) -> Result<Vec<ImportCandidate>, String> {
+    let data = Vault::read(contents, password)?;
+    Ok(data
+        .accounts
+        .into_iter()
+        .map(|stored| ImportCandidate {
+            account: stored.account.clone(),
+            username: stored.username.clone(),
+            parsed: Account::try_from(stored),
+        })
+        .collect())
+}
+
+/// Recognizes the backup format from its structure and reads its entries.
+fn parse_backup(contents: &[u8], password: Option<&str>) -> Result<(BackupFormat, Vec<ImportCandidate>), String> {
+    let Ok(json) = serde_json::from_slice::<serde_json::Value>(contents) else {
+        return match std::str::from_utf8(contents) {
+            Ok(text) if text.lines().any(|line| line.trim_start().starts_with("otpauth://") || line.trim_start().starts_with("steam://")) => {
+                Ok((BackupFormat::UriList, parse_uri_list(text)))
+            }
+            // andOTP's encrypted backups are the only binary format.
+            _ => Ok((BackupFormat::AndOtp, andotp::parse_encrypted(contents, require_password(password)?)?)),
+        };
+    };
+
+    if json.get("kdf").is_some() && json.get("ciphertext").is_some() {
+        Ok((BackupFormat::Totlaunloc, parse_native(contents, require_password(password)?)?))
+    } else if json.get("header").is_some() && json.get("db").is_some() {
+        Ok((BackupFormat::Aegis, aegis::parse(json, password)?))
+    } else if json.get("services").is_some() || json.get("servicesEncrypted").is_some() {
+        Ok((BackupFormat::TwoFas, twofas::parse(json, password)?))
+    } else if json.get("tokens").is_some() {
+        Ok((BackupFormat::FreeOtpPlus, freeotp::parse(json)?))
+    } else if json.is_array() {
+        Ok((BackupFormat::AndOtp, andotp::parse(json)?))
+    } else {
+        Err("Unrecognized backup format".to_string())
+    }
+}
+
+/// Reads a backup from Aegis, 2FAS, andOTP, FreeOTP+ or this app (or a list of
+/// otpauth URLs) and lists what importing it would do. Nothing is added
+/// until `commit_import` is called with the returned id.
+#[tauri::command]
+pub fn preview_import(contents: Vec<u8>, password: Option<String>, state: State<AppState>) -> Result<ImportPreview, String> {
+    if state.vault.lock().unwrap().is_none() {
+        return Err("The TOTP store is locked".to_string());
+    }
+
+    let (format, candidates) = parse_backup(&contents, password.as_deref())?;
+
+    // Duplicates are checked against the store and earlier entries alike.
+    let mut known = state.accounts.lock().unwrap().clone();
+    let mut entries = Vec::with_capacity(candidates.len());
+    for (index, candidate) in candidates.iter().enumerate() {
+        let parsed = candidate.parsed.as_ref().ok();
+        let status = match &candidate.parsed {
+            Err(reason) => PreviewStatus::Unsupported { reason: reason.clone() },
+            Ok(account) if is_duplicate(&known, account) => PreviewStatus::Duplicate,
+            Ok(account) => {
+                known.push(account.clone());
+                PreviewStatus::New
+            }
+        };
+        entries.push(PreviewEntry {
+            index,
+            account: candidate.account.clone(),
+            username: candidate.username.clone(),
+            kind: parsed.map(|a| a.kind),
+            algorithm: parsed.map(|a| algorithm_name(a.totp.algorithm).to_string()),
+            digits: parsed.map(|a| a.totp.digits),
+            period: parsed.filter(|a| a.kind == OtpKind::Totp).map(|a| a.totp.step),
+            status,
+        });
+    }
+
+    let import_id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let mut pending_imports = state.pending_imports.lock().unwrap();
+    pending_imports.retain(|_, pending| pending.expires_at > now);
+    pending_imports.insert(
+        import_id.clone(),
+        PendingImport {
+            candidates,
+            expires_at: now + Duration::minutes(IMPORT_TTL_MINUTES),
+        },
+    );
+
+    Ok(ImportPreview { import_id, format, entries })
+}
+
+/// Adds the previewed accounts, or only those at `indices` when given.
+/// Duplicates are skipped again against the accounts present now. Previews
+/// expire [`IMPORT_TTL_MINUTES`] after they were made.
+#[tauri::command]
+pub fn commit_import(import_id: String, indices: Option<Vec<usize>>, state: State<AppState>) -> Result<Vec<ImportOutcome>, String> {
+    let pending = state.pending_imports.lock().unwrap().remove(&import_id).ok_or_else(|| format!("Import not found: {}", import_id))?;
+    if Utc::now() >= pending.expires_at {
+        return Err(format!("Import {} expired at {}; preview the backup again", import_id, pending.expires_at));
+    }
+
+    let candidates = pending
+        .candidates
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| indices.as_ref().is_none_or(|selected| selected.contains(index)))
+        .map(|(_, candidate)| candidate)
+        .collect();
+
+    add_candidates(&state, candidates)
+}
+
+#[tauri::command]
+pub fn cancel_import(import_id: String, state: State<AppState>) {
+    state.pending_imports.lock().unwrap().remove(&import_id);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::totp::DEFAULT_ICON;
+
+    const PASSWORD: &str = "test-password";
+    /// `JBSWY3DPEHPK3PXP` decoded.
+    const SECRET: &[u8] = b"Hello!\xde\xad\xbe\xef";
+
+    const AEGIS_PLAIN: &str = r#"{"version":1,"header":{"slots":null,"params":null},"db":{"version":2,"entries":[{"type":"totp","uuid":"u1","name":"alice","issuer":"Example","icon":"PHN2Zy8+","icon_mime":"image/svg+xml","info":{"secret":"JBSWY3DPEHPK3PXP","algo":"SHA256","digits":8,"period":60}}]}}"#;
+    /// A TOTP and a HOTP entry under a password slot with scrypt N = 1024.
+    const AEGIS_ENCRYPTED: &str = r#"{"version":1,"header":{"slots":[{"type":1,"uuid":"s","key":"555675e5e52bca8a25df65de5be816e0097fbeb96b7c84f04abee6e72a05579b","key_params":{"nonce":"010101010101010101010101","tag":"cfe151a6cea75f0d821091b61253ca58"},"n":1024,"r":8,"p":1,"salt":"202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f","repaired":true}],"params":{"nonce":"020202020202020202020202","tag":"9e01d2731071ecb03301f7ba33bf3a70"}},"db":"4Apv3suqWDSlp5jKH1u/fWibxgnndkKqOXylegcOa/jyjLcWbxTf+qwf69XIwmztQ/sgvyjItQEu6GjTcXANMf7vcQRMWSr0mJ835QlGa4uN2uqVjufXhFjTg5XN/ETFbb1lv05XVzvZiSLqnws2li9A48hQeQi1qvywZE+uSZIzXiz+wphw1DoLwNJiH/jgMSJvAxNaqRHv/7WnKmpZ6iB1KshWGeZZCCd0mh7fTXxyifiJLUCTUZnGnNuVt3pZDlZfvmj/3zQRRU4I15SkLdal+B5rpWmfA1RHEvASX8KTedFoxb7J1uq+WKqqDX5Y0PTw0kXY01GiUF2jny26PEUULBIJpLZh89lwDGiToo7aQ/mwKEKj44dTgX64ZpxglBNpuW+Y3HvRK7BGddXI+yO9mR5ON0YVXzEniAkkXQ=="}"#;
+    const TWOFAS_ENCRYPTED: &str = r#"{"services":[],"servicesEncrypted":"WvSqwgp5RMgp65HOP0KVW6D+6BL052HTszOSi0cgxoW/3Yrtx9TK2+htPVJaoSPk4yyc3+hP4nz7xf+VP6Ik9cCUDZ8vrbvsE2/TCseaMzRe2ahF/zF/XbxtJQ6LgTuFWSgKVjmxdng3FIk4cnQT6eNFJ9SKVEYLrLe6GRo0gWdilkJlXyko4ZUoEZEbleFdr/UCh68Ey4h+iqLjfJPZ8F5h0qxD3sKkvsk=:QEFCQ0RFRkdISUpLTE1OT1BRUlNUVVZXWFlaW1xdXl8=:AwMDAwMDAwMDAwMD","schemaVersion":4}"#;
+    const TWOFAS_PLAIN: &str = r#"{"services":[{"name":"Example","secret":"JBSWY3DPEHPK3PXP","otp":{"account":"alice","digits":6,"period":30,"algorithm":"SHA1","tokenType":"TOTP"}}],"schemaVersion":4}"#;
+    const ANDOTP_PLAIN: &str = r#"[{"secret":"JBSWY3DPEHPK3PXP","issuer":"Example","label":"alice","digits":6,"type":"TOTP","algorithm":"SHA1","period":30},{"secret":"JBSWY3DPEHPK3PXP","label":"gaben","type":"STEAM"}]"#;
+    /// 1000 PBKDF2 iterations.
+    const ANDOTP_ENCRYPTED: &str = "000003e80404040404040404040404040505050505050505050505058f462329b30a43b26217d440fa1e1e4b8249bd57c46df7673cf23154d42e19d85ca64ae41436b743a7a637dac00ae3d0a035fb01af441e6ff05aae4bc54d627e23515f1de7512203c71f25dc8c3465c3ed2b2db16ca8eb15d11554934dcfd7f3cbfb9391ca9fbc7e65c4cee4ca3198c95230ef5593317f930acbbfbcae047f7c8ccb740afa807acd02d0";
+    const FREEOTP: &str = r#"{"tokens":[{"algo":"SHA512","digits":7,"issuerExt":"Example","label":"alice","period":45,"secret":[72,101,108,108,111,33,-34,-83,-66,-17],"type":"TOTP"}]}"#;
+
+    fn accounts(contents: &[u8], password: Option<&str>) -> (BackupFormat, Vec<Account>) {
+        let (format, candidates) = parse_backup(contents, password).unwrap();
+        (format, candidates.into_iter().map(|c| c.parsed.unwrap()).collect())
+    }
+
+    fn assert_example(account: &Account, algorithm: Algorithm, digits: usize, period: u64) {
+        assert_eq!((account.account.as_str(), account.username.as_str()), ("Example", "alice"));
+        assert_eq!(account.totp.secret, SECRET);
+        assert_eq!(account.totp.algorithm, algorithm);
+        assert_eq!(account.totp.digits, digits);
+        assert_eq!(account.totp.step, period);
+    }
+
+    #[test]
+    fn reads_aegis_backups() {
+        let (format, plain) = accounts(AEGIS_PLAIN.as_bytes(), None);
+        assert!(format == BackupFormat::Aegis);
+        assert_example(&plain[0], Algorithm::SHA256, 8, 60);
+        assert_eq!(plain[0].icon, "data:image/svg+xml;base64,PHN2Zy8+");
+
+        let (_, encrypted) = accounts(AEGIS_ENCRYPTED.as_bytes(), Some(PASSWORD));
+        assert_example(&encrypted[0], Algorithm::SHA256, 8, 60);
+        assert_eq!(encrypted[1].kind, OtpKind::Hotp);
+        assert_eq!(encrypted[1].counter, 5);
+
+        assert!(parse_backup(AEGIS_ENCRYPTED.as_bytes(), Some("wrong password")).is_err());
+        assert!(parse_backup(AEGIS_ENCRYPTED.as_bytes(), None).is_err());
+    }
+
+    #[test]
+    fn drops_icons_that_are_not_images() {
+        let crafted = AEGIS_PLAIN.replace("image/svg+xml", "text/html");
+        let (_, accounts) = accounts(crafted.as_bytes(), None);
+        assert_eq!(accounts[0].icon, DEFAULT_ICON);
+    }
+
+    #[test]
+    fn rejects_oversized_scrypt_cost() {
+        let crafted = AEGIS_ENCRYPTED.replace(r#""n":1024"#, r#""n":1073741824"#);
+        assert_eq!(parse_backup(crafted.as_bytes(), Some(PASSWORD)).err().as_deref(), Some("Key derivation parameters of the backup exceed the supported limits"));
+        let crafted = AEGIS_ENCRYPTED.replace(r#""n":1024"#, r#""n":1000"#);
+        assert!(parse_backup(crafted.as_bytes(), Some(PASSWORD)).err().unwrap().contains("not a power of two"));
+    }
+
+    #[test]
+    fn reads_twofas_backups() {
+        let (format, plain) = accounts(TWOFAS_PLAIN.as_bytes(), None);
+        assert!(format == BackupFormat::TwoFas);
+        assert_example(&plain[0], Algorithm::SHA1, 6, 30);
+
+        let (_, encrypted) = accounts(TWOFAS_ENCRYPTED.as_bytes(), Some(PASSWORD));
+        assert_example(&encrypted[0], Algorithm::SHA1, 6, 30);
+        assert!(parse_backup(TWOFAS_ENCRYPTED.as_bytes(), Some("wrong password")).is_err());
+    }
+
+    #[test]
+    fn reads_andotp_backups() {
+        let (format, plain) = accounts(ANDOTP_PLAIN.as_bytes(), None);
+        assert!(format == BackupFormat::AndOtp);
+        assert_example(&plain[0], Algorithm::SHA1, 6, 30);
+        assert_eq!((plain[1].account.as_str(), plain[1].totp.algorithm), ("Steam", Algorithm::Steam));
+
+        let (format, encrypted) = accounts(&hex::decode(ANDOTP_ENCRYPTED).unwrap(), Some(PASSWORD));
+        assert!(format == BackupFormat::AndOtp);
+        assert_example(&encrypted[0], Algorithm::SHA1, 6, 30);
+    }
+
+    #[test]
+    fn rejects_unknown_binary_files() {
+        // Read as andOTP, whose first four bytes would be the iteration count.
+        let junk = [0xffu8; 64];
+        assert_eq!(parse_backup(&junk, Some(PASSWORD)).err().as_deref(), Some("Unrecognized backup format"));
+    }
+
+    #[test]
+    fn reads_freeotp_backups() {
+        let (format, accounts) = accounts(FREEOTP.as_bytes(), None);
+        assert!(format == BackupFormat::FreeOtpPlus);
+        assert_example(&accounts[0], Algorithm::SHA512, 7, 45);
+    }
+
+    #[test]
+    fn aegis_export_round_trip() {
+        let (_, mut exported) = accounts(AEGIS_ENCRYPTED.as_bytes(), Some(PASSWORD));
+        exported[0].icon = "data:image/png;base64,iVBORw0=".to_string();
+
+        for password in [None, Some(PASSWORD)] {
+            let contents = aegis::export(&exported, password).unwrap();
+            let (format, imported) = accounts(&contents, password);
+            assert!(format == BackupFormat::Aegis);
+            assert_eq!(imported.len(), exported.len());
+            for (imported, exported) in imported.iter().zip(&exported) {
+                assert_eq!((&imported.account, &imported.username), (&exported.account, &exported.username));
+                assert_eq!((imported.kind, imported.counter), (exported.kind, exported.counter));
+                assert_eq!(imported.totp, exported.totp);
+                assert_eq!(imported.icon, exported.icon);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_uri_lists() {
+        let list = "otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP\n\nnot a url\n";
+        let (format, candidates) = parse_backup(list.as_bytes(), None).unwrap();
+        assert!(format == BackupFormat::UriList);
+        assert_eq!(candidates.len(), 2);
+        assert_example(candidates[0].parsed.as_ref().unwrap(), Algorithm::SHA1, 6, 30);
+        assert!(candidates[1].parsed.is_err());
+    }
+}
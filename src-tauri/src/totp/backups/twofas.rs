@@ -0,0 +1,81 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pbkdf2::pbkdf2_hmac;
+use serde::Deserialize;
+use sha2::Sha256;
+
+use super::{aes_gcm_decrypt, candidate, require_password, BackupEntry, Secret};
+use crate::totp::import::ImportCandidate;
+
+const PBKDF2_ITERATIONS: u32 = 10_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFasFile {
+    #[serde(default)]
+    services: Vec<TwoFasService>,
+    services_encrypted: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TwoFasService {
+    name: String,
+    secret: String,
+    otp: TwoFasOtp,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFasOtp {
+    account: Option<String>,
+    issuer: Option<String>,
+    digits: Option<usize>,
+    period: Option<u64>,
+    algorithm: Option<String>,
+    token_type: Option<String>,
+    counter: Option<u64>,
+}
+
+/// `servicesEncrypted` is `ciphertext:salt:iv`, each base64, with the
+/// key derived by PBKDF2-HMAC-SHA256.
+fn decrypt_services(encrypted: &str, password: &str) -> Result<Vec<TwoFasService>, String> {
+    let parts = encrypted
+        .split(':')
+        .map(|part| STANDARD.decode(part).map_err(|e| format!("Invalid encrypted data in 2FAS backup: {}", e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [ciphertext, salt, iv] = parts.as_slice() else {
+        return Err("Invalid encrypted data in 2FAS backup".to_string());
+    };
+
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, PBKDF2_ITERATIONS, &mut key);
+    let plaintext = aes_gcm_decrypt(&key, iv, ciphertext).ok_or("Incorrect password for the 2FAS backup")?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse decrypted 2FAS services: {}", e))
+}
+
+/// Reads a 2FAS `.2fas` backup, plain or password-protected.
+pub fn parse(json: serde_json::Value, password: Option<&str>) -> Result<Vec<ImportCandidate>, String> {
+    let file: TwoFasFile = serde_json::from_value(json).map_err(|e| format!("Failed to parse 2FAS backup: {}", e))?;
+
+    let services = match &file.services_encrypted {
+        Some(encrypted) => decrypt_services(encrypted, require_password(password)?)?,
+        None => file.services,
+    };
+
+    Ok(services
+        .into_iter()
+        .map(|service| {
+            candidate(BackupEntry {
+                kind: service.otp.token_type.unwrap_or_else(|| "TOTP".to_string()),
+                issuer: service.otp.issuer.filter(|issuer| !issuer.is_empty()).unwrap_or(service.name),
+                name: service.otp.account.unwrap_or_default(),
+                secret: Secret::Base32(service.secret),
+                algorithm: service.otp.algorithm,
+                digits: service.otp.digits,
+                period: service.otp.period,
+                counter: service.otp.counter,
+                icon: None,
+            })
+        })
+        .collect())
+}
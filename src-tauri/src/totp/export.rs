@@ -0,0 +1,124 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::Local;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, State};
+
+use super::{
+    backups::aegis,
+    confirm_secret,
+    store::Vault,
+    to_otpauth,
+    types::{Account, AppState, StoreData, StoredAccount},
+};
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One `otpauth://` URL per line, unencrypted.
+    UriList,
+    /// An Aegis vault, encrypted when a password is given.
+    Aegis,
+    /// A copy of the app's own store under its own password, for
+    /// `preview_import` on another machine.
+    Totlaunloc,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::UriList => "txt",
+            ExportFormat::Aegis => "json",
+            ExportFormat::Totlaunloc => "vault",
+        }
+    }
+}
+
+/// Creates `path` readable only by the user and writes `contents` to it.
+/// Fails if the file already exists rather than replacing it.
+fn write_new_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Writes all accounts, or those in `ids`, to a new file in the downloads
+/// directory and returns its path. Exports reveal the secrets, so the store
+/// secret is asked for again and the file is only readable by the user.
+/// `password` encrypts the export; the app's own format requires one.
+#[tauri::command]
+pub fn export_accounts(
+    app: AppHandle,
+    format: ExportFormat,
+    ids: Option<Vec<u32>>,
+    secret: String,
+    password: Option<String>,
+    state: State<AppState>,
+) -> Result<String, String> {
+    confirm_secret(&state, &secret)?;
+
+    let accounts: Vec<Account> = state
+        .accounts
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|acc| ids.as_ref().is_none_or(|ids| ids.contains(&acc.id)))
+        .cloned()
+        .collect();
+    if accounts.is_empty() {
+        return Err("No accounts to export".to_string());
+    }
+
+    let password = password.filter(|p| !p.is_empty());
+    let dir = app.path().download_dir().map_err(|e| format!("Failed to get downloads directory: {}", e))?;
+    let path = dir.join(format!("totlaunloc-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), format.extension()));
+
+    let contents = match format {
+        ExportFormat::UriList => accounts.iter().map(|acc| to_otpauth(acc) + "\n").collect::<String>().into_bytes(),
+        ExportFormat::Aegis => aegis::export(&accounts, password.as_deref())?,
+        ExportFormat::Totlaunloc => {
+            let password = password.ok_or("A password is required for this backup")?;
+            let data = StoreData {
+                next_id: accounts.iter().map(|acc| acc.id + 1).max().unwrap_or_default(),
+                accounts: accounts.iter().map(StoredAccount::from).collect(),
+            };
+            Vault::create(&path, &password)?.seal(&data)?
+        }
+    };
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    write_new_file(&path, &contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_files_are_private_and_never_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.txt");
+        write_new_file(&path, b"otpauth://").unwrap();
+        assert!(write_new_file(&path, b"other").is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"otpauth://");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
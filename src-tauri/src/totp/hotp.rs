@@ -0,0 +1,118 @@
+use tauri::State;
+
+use super::{
+    modify,
+    types::{Account, AppState, HotpCode, OtpKind},
+};
+
+/// HOTP accounts reuse `TOTP` with a one-second step, so the time passed to
+/// `generate` is the counter itself.
+pub const HOTP_STEP: u64 = 1;
+
+/// How many counter values past the current one `resync_hotp` searches by
+/// default.
+const DEFAULT_LOOK_AHEAD: u64 = 100;
+
+/// Upper bound on the look-ahead window, which is searched code by code.
+const MAX_LOOK_AHEAD: u64 = 1000;
+
+const COUNTER_EXHAUSTED: &str = "The counter cannot be advanced any further";
+
+fn hotp_code(account: &Account, counter: u64) -> String {
+    account.totp.generate(counter * HOTP_STEP)
+}
+
+fn find_hotp(accounts: &mut [Account], id: u32) -> Result<&mut Account, String> {
+    let account = accounts.iter_mut().find(|acc| acc.id == id).ok_or("Account not found")?;
+    if account.kind != OtpKind::Hotp {
+        return Err("Account is not counter-based".to_string());
+    }
+    Ok(account)
+}
+
+fn issue_code(account: &mut Account) -> Result<HotpCode, String> {
+    let counter = account.counter;
+    account.counter = counter.checked_add(1).ok_or(COUNTER_EXHAUSTED)?;
+    Ok(HotpCode { code: hotp_code(account, counter), counter })
+}
+
+/// Issues the code for the current counter and advances the counter. The
+/// new counter is saved before the code is returned, so a code is never
+/// issued twice.
+#[tauri::command]
+pub fn next_hotp_code(id: u32, state: State<AppState>) -> Result<HotpCode, String> {
+    modify(&state, |accounts, _| issue_code(find_hotp(accounts, id)?))
+}
+
+/// The counter following two consecutive codes found within `look_ahead`
+/// values past the account's current counter.
+fn resync_counter(account: &Account, first_code: &str, second_code: &str, look_ahead: u64) -> Result<u64, String> {
+    let start = account.counter;
+    let found = (start..=start.saturating_add(look_ahead))
+        .find(|&counter| counter.checked_add(1).is_some_and(|next| hotp_code(account, counter) == first_code && hotp_code(account, next) == second_code))
+        .ok_or_else(|| format!("The codes do not match any of the next {} counter values", look_ahead))?;
+    found.checked_add(2).ok_or_else(|| COUNTER_EXHAUSTED.to_string())
+}
+
+/// Brings the counter back in line with the server after codes were
+/// generated but never used. Searches `look_ahead` (at most 1000) counter
+/// values past the current one for two consecutive codes entered by the user
+/// and moves the counter past them. Returns the new counter.
+#[tauri::command]
+pub fn resync_hotp(id: u32, first_code: String, second_code: String, look_ahead: Option<u64>, state: State<AppState>) -> Result<u64, String> {
+    let (first_code, second_code) = (first_code.trim().to_string(), second_code.trim().to_string());
+    let look_ahead = look_ahead.unwrap_or(DEFAULT_LOOK_AHEAD).min(MAX_LOOK_AHEAD);
+
+    modify(&state, |accounts, _| {
+        let account = find_hotp(accounts, id)?;
+        account.counter = resync_counter(account, &first_code, &second_code, look_ahead)?;
+        Ok(account.counter)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::totp::build_totp;
+    use totp_rs::Algorithm;
+
+    /// The RFC 4226 appendix D test secret, starting at `counter`.
+    fn rfc_account(counter: u64) -> Account {
+        let totp = build_totp(Algorithm::SHA1, 6, HOTP_STEP, b"12345678901234567890".to_vec()).unwrap();
+        Account::new("Example".to_string(), "alice".to_string(), totp, OtpKind::Hotp, counter)
+    }
+
+    #[test]
+    fn generates_rfc_4226_codes() {
+        let account = rfc_account(0);
+        assert_eq!(hotp_code(&account, 0), "755224");
+        assert_eq!(hotp_code(&account, 1), "287082");
+        assert_eq!(hotp_code(&account, 9), "520489");
+    }
+
+    #[test]
+    fn resync_moves_past_the_matching_codes() {
+        // Codes for counters 5 and 6.
+        assert_eq!(resync_counter(&rfc_account(1), "254676", "287922", 10), Ok(7));
+    }
+
+    #[test]
+    fn resync_respects_the_look_ahead_window() {
+        assert!(resync_counter(&rfc_account(1), "254676", "287922", 3).is_err());
+        // Codes behind the current counter are never matched.
+        assert!(resync_counter(&rfc_account(7), "254676", "287922", 10).is_err());
+    }
+
+    #[test]
+    fn counter_never_wraps_around() {
+        let mut account = rfc_account(u64::MAX);
+        assert_eq!(issue_code(&mut account).err().unwrap(), COUNTER_EXHAUSTED);
+        assert_eq!(account.counter, u64::MAX);
+
+        let mut account = rfc_account(u64::MAX - 1);
+        let (first, second) = (hotp_code(&account, u64::MAX - 1), hotp_code(&account, u64::MAX));
+        assert_eq!(resync_counter(&account, &first, &second, 10), Err(COUNTER_EXHAUSTED.to_string()));
+        assert_eq!(issue_code(&mut account).unwrap().code, first);
+        assert_eq!(account.counter, u64::MAX);
+    }
+}
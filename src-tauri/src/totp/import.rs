@@ -0,0 +1,86 @@
+use serde::Serialize;
+use tauri::State;
+
+use super::{modify, parse_otpauth, types::{Account, AppState}};
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ImportStatus {
+    Added,
+    Duplicate,
+    Unsupported { reason: String },
+}
+
+#[derive(Serialize)]
+pub struct ImportOutcome {
+    pub account: String,
+    pub username: String,
+    #[serde(flatten)]
+    pub status: ImportStatus,
+}
+
+/// An entry read from an export, before it is added: the labels it was
+/// found under and either the account or why it cannot be imported.
+pub struct ImportCandidate {
+    pub account: String,
+    pub username: String,
+    pub parsed: Result<Account, String>,
+}
+
+/// Same secret, type and labels as an account already in the list.
+pub fn is_duplicate(accounts: &[Account], candidate: &Account) -> bool {
+    accounts.iter().any(|acc| {
+        acc.totp.secret == candidate.totp.secret && acc.kind == candidate.kind && acc.account == candidate.account && acc.username == candidate.username
+    })
+}
+
+/// Adds every importable candidate that is not a duplicate, including of an
+/// earlier candidate in the same batch, and saves the store once.
+pub fn add_candidates(state: &AppState, candidates: Vec<ImportCandidate>) -> Result<Vec<ImportOutcome>, String> {
+    modify(state, |accounts, next_id| {
+        let mut outcomes = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let status = match candidate.parsed {
+                Err(reason) => ImportStatus::Unsupported { reason },
+                Ok(account) if is_duplicate(accounts, &account) => ImportStatus::Duplicate,
+                Ok(mut account) => {
+                    account.id = *next_id;
+                    *next_id += 1;
+                    accounts.push(account);
+                    ImportStatus::Added
+                }
+            };
+            outcomes.push(ImportOutcome {
+                account: candidate.account,
+                username: candidate.username,
+                status,
+            });
+        }
+        Ok(outcomes)
+    })
+}
+
+/// Reads one `otpauth://` or `steam://` URL as an import candidate.
+pub fn uri_candidate(uri: &str) -> ImportCandidate {
+    match parse_otpauth(uri) {
+        Ok(account) => ImportCandidate {
+            account: account.account.clone(),
+            username: account.username.clone(),
+            parsed: Ok(account),
+        },
+        Err(e) => ImportCandidate {
+            account: uri.split('?').next().unwrap_or(uri).to_string(),
+            username: String::new(),
+            parsed: Err(e.to_string()),
+        },
+    }
+}
+
+/// Adds several `otpauth://` or `steam://` URLs at once, such as the QR
+/// codes scanned from one image. Accounts already in the store are skipped,
+/// so adding the same URLs again changes nothing.
+#[tauri::command]
+pub fn import_uris(uris: Vec<String>, state: State<AppState>) -> Result<Vec<ImportOutcome>, String> {
+    let candidates = uris.iter().map(|uri| uri_candidate(uri.trim())).collect();
+    add_candidates(&state, candidates)
+}
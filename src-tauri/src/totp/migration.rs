@@ -0,0 +1,266 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use prost::Message;
+use serde::Serialize;
+use tauri::State;
+use totp_rs::Algorithm;
+use url::Url;
+
+use super::{
+    build_totp,
+    hotp::HOTP_STEP,
+    import::{add_candidates, ImportCandidate, ImportOutcome},
+    types::{Account, AppState, OtpKind},
+};
+
+// The `MigrationPayload` message of Google Authenticator's export format.
+
+#[derive(Clone, PartialEq, Message)]
+struct MigrationPayload {
+    #[prost(message, repeated, tag = "1")]
+    otp_parameters: Vec<OtpParameters>,
+    #[prost(int32, tag = "2")]
+    version: i32,
+    #[prost(int32, tag = "3")]
+    batch_size: i32,
+    #[prost(int32, tag = "4")]
+    batch_index: i32,
+    #[prost(int32, tag = "5")]
+    batch_id: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct OtpParameters {
+    #[prost(bytes = "vec", tag = "1")]
+    secret: Vec<u8>,
+    #[prost(string, tag = "2")]
+    name: String,
+    #[prost(string, tag = "3")]
+    issuer: String,
+    #[prost(enumeration = "MigrationAlgorithm", tag = "4")]
+    algorithm: i32,
+    #[prost(enumeration = "DigitCount", tag = "5")]
+    digits: i32,
+    #[prost(enumeration = "OtpType", tag = "6")]
+    otp_type: i32,
+    #[prost(int64, tag = "7")]
+    counter: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum MigrationAlgorithm {
+    Unspecified = 0,
+    Sha1 = 1,
+    Sha256 = 2,
+    Sha512 = 3,
+    Md5 = 4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum DigitCount {
+    Unspecified = 0,
+    Six = 1,
+    Eight = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+enum OtpType {
+    Unspecified = 0,
+    Hotp = 1,
+    Totp = 2,
+}
+
+#[derive(Serialize)]
+pub struct MigrationImport {
+    pub outcomes: Vec<ImportOutcome>,
+    /// Indices of the parts of a multi-part export that were not supplied.
+    pub missing_parts: Vec<i32>,
+}
+
+/// Far more parts than an export of any realistic size needs; bounds the
+/// list of missing parts built from an untrusted `batch_size`.
+const MAX_BATCH_SIZE: i32 = 100;
+
+fn decode_payload(uri: &str) -> Result<MigrationPayload, String> {
+    let url = Url::parse(uri.trim()).map_err(|e| format!("Invalid migration URL: {}", e))?;
+    if url.scheme() != "otpauth-migration" {
+        return Err("Not an otpauth-migration:// URL".to_string());
+    }
+
+    let data = url
+        .query_pairs()
+        .find(|(key, _)| key == "data")
+        .map(|(_, value)| value.into_owned())
+        .ok_or("Migration URL has no data")?;
+    // An unescaped `+` in the query decodes to a space.
+    let data = STANDARD.decode(data.replace(' ', "+")).map_err(|e| format!("Invalid migration data: {}", e))?;
+
+    MigrationPayload::decode(data.as_slice()).map_err(|e| format!("Failed to decode migration data: {}", e))
+}
+
+fn to_account(params: &OtpParameters) -> Result<Account, String> {
+    let algorithm = match MigrationAlgorithm::try_from(params.algorithm) {
+        Ok(MigrationAlgorithm::Unspecified) | Ok(MigrationAlgorithm::Sha1) => Algorithm::SHA1,
+        Ok(MigrationAlgorithm::Sha256) => Algorithm::SHA256,
+        Ok(MigrationAlgorithm::Sha512) => Algorithm::SHA512,
+        Ok(MigrationAlgorithm::Md5) => return Err("MD5 is not supported".to_string()),
+        Err(_) => return Err(format!("Unknown algorithm: {}", params.algorithm)),
+    };
+    let digits = match DigitCount::try_from(params.digits) {
+        Ok(DigitCount::Unspecified) | Ok(DigitCount::Six) => 6,
+        Ok(DigitCount::Eight) => 8,
+        Err(_) => return Err(format!("Unknown digit count: {}", params.digits)),
+    };
+    let (kind, period) = match OtpType::try_from(params.otp_type) {
+        Ok(OtpType::Unspecified) | Ok(OtpType::Totp) => (OtpKind::Totp, 30),
+        Ok(OtpType::Hotp) => (OtpKind::Hotp, HOTP_STEP),
+        Err(_) => return Err(format!("Unknown OTP type: {}", params.otp_type)),
+    };
+    let counter = u64::try_from(params.counter).map_err(|_| format!("Invalid counter: {}", params.counter))?;
+
+    let (account, username) = labels(params);
+    let totp = build_totp(algorithm, digits, period, params.secret.clone())?;
+    Ok(Account::new(account, username, totp, kind, counter))
+}
+
+/// Checks that the parts belong to one export and returns the indices of
+/// the parts that are missing.
+fn missing_parts(payloads: &[MigrationPayload]) -> Result<Vec<i32>, String> {
+    let first = payloads.first().ok_or("No migration URLs given")?;
+
+    if payloads.iter().any(|p| p.batch_id != first.batch_id || p.batch_size != first.batch_size) {
+        return Err("The migration URLs belong to different exports".to_string());
+    }
+    // Older exports leave the batch fields unset.
+    let batch_size = first.batch_size.max(1);
+    if batch_size > MAX_BATCH_SIZE {
+        return Err(format!("The export claims {} parts; at most {} are supported", batch_size, MAX_BATCH_SIZE));
+    }
+    if let Some(p) = payloads.iter().find(|p| !(0..batch_size).contains(&p.batch_index)) {
+        return Err(format!("Invalid part index {} in an export of {} parts", p.batch_index, batch_size));
+    }
+
+    let mut indices: Vec<i32> = payloads.iter().map(|p| p.batch_index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    Ok((0..batch_size).filter(|i| indices.binary_search(i).is_err()).collect())
+}
+
+/// Names are exported as `issuer:account` or just `account`.
+fn labels(params: &OtpParameters) -> (String, String) {
+    match params.name.split_once(':') {
+        Some((issuer, user)) if params.issuer.is_empty() || issuer == params.issuer => (issuer.to_string(), user.trim().to_string()),
+        _ => (params.issuer.clone(), params.name.clone()),
+    }
+}
+
+/// Imports the accounts of a Google Authenticator export. A large export is
+/// split across several QR codes; pass the URL of each scanned part. Parts
+/// that were not supplied are reported rather than failing the import.
+#[tauri::command]
+pub fn import_migration(uris: Vec<String>, state: State<AppState>) -> Result<MigrationImport, String> {
+    let payloads = uris.iter().map(|uri| decode_payload(uri)).collect::<Result<Vec<_>, _>>()?;
+    let missing_parts = missing_parts(&payloads)?;
+
+    // A part scanned twice is only imported once.
+    let mut seen = Vec::new();
+    let mut candidates = Vec::new();
+    for payload in &payloads {
+        if seen.contains(&payload.batch_index) {
+            continue;
+        }
+        seen.push(payload.batch_index);
+
+        for params in &payload.otp_parameters {
+            let (account, username) = labels(params);
+            candidates.push(ImportCandidate {
+                account,
+                username,
+                parsed: to_account(params),
+            });
+        }
+    }
+
+    Ok(MigrationImport {
+        outcomes: add_candidates(&state, candidates)?,
+        missing_parts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two accounts in one part of batch 1234: a SHA1 TOTP account
+    /// `Example:alice` and an 8-digit SHA256 HOTP account `bob` at counter 7,
+    /// both with the secret `JBSWY3DPEHPK3PXP`.
+    const DATA: &str = "CioKCkhlbGxvId6tvu8SDUV4YW1wbGU6YWxpY2UaB0V4YW1wbGUgASgBMAIKGQoKSGVsbG8h3q2+7xIDYm9iIAIoAjABOAcQARgBIAAo0gk=";
+
+    #[test]
+    fn decodes_escaped_and_unescaped_data() {
+        let escaped = format!("otpauth-migration://offline?data={}", DATA.replace('+', "%2B").replace('=', "%3D"));
+        let unescaped = format!("otpauth-migration://offline?data={}", DATA);
+        for uri in [escaped, unescaped] {
+            let payload = decode_payload(&uri).unwrap();
+            assert_eq!(payload.batch_id, 1234);
+            assert_eq!(payload.batch_size, 1);
+            assert_eq!(payload.otp_parameters.len(), 2);
+        }
+    }
+
+    #[test]
+    fn converts_parameters_to_accounts() {
+        let payload = decode_payload(&format!("otpauth-migration://offline?data={}", DATA)).unwrap();
+
+        let totp = to_account(&payload.otp_parameters[0]).unwrap();
+        assert_eq!((totp.account.as_str(), totp.username.as_str()), ("Example", "alice"));
+        assert_eq!(totp.kind, OtpKind::Totp);
+        assert_eq!(totp.totp.secret, b"Hello!\xde\xad\xbe\xef");
+        assert_eq!(totp.totp.digits, 6);
+
+        let hotp = to_account(&payload.otp_parameters[1]).unwrap();
+        assert_eq!((hotp.account.as_str(), hotp.username.as_str()), ("", "bob"));
+        assert_eq!(hotp.kind, OtpKind::Hotp);
+        assert_eq!(hotp.totp.algorithm, Algorithm::SHA256);
+        assert_eq!(hotp.totp.digits, 8);
+        assert_eq!(hotp.counter, 7);
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_md5() {
+        assert!(decode_payload("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP").is_err());
+
+        let md5 = OtpParameters {
+            secret: b"Hello!".to_vec(),
+            algorithm: MigrationAlgorithm::Md5 as i32,
+            ..Default::default()
+        };
+        assert!(to_account(&md5).is_err());
+    }
+
+    fn part(batch_size: i32, batch_index: i32) -> MigrationPayload {
+        MigrationPayload {
+            batch_size,
+            batch_index,
+            batch_id: 1234,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_missing_parts() {
+        assert_eq!(missing_parts(&[part(3, 1), part(3, 1)]), Ok(vec![0, 2]));
+        assert_eq!(missing_parts(&[part(0, 0)]), Ok(vec![]));
+    }
+
+    #[test]
+    fn rejects_inconsistent_or_oversized_batches() {
+        assert!(missing_parts(&[part(2, 2)]).is_err());
+        assert!(missing_parts(&[part(2, -1)]).is_err());
+        assert!(missing_parts(&[part(2, 0), part(3, 1)]).is_err());
+        assert!(missing_parts(&[part(i32::MAX, 0)]).is_err());
+        assert!(missing_parts(&[]).is_err());
+    }
+}
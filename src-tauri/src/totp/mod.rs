@@ -0,0 +1,609 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use percent_encoding::percent_decode_str;
+use tauri::{AppHandle, Manager, State};
+use totp_rs::{Algorithm, TOTP};
+use url::Url;
+
+pub mod backups;
+pub mod export;
+pub mod hotp;
+pub mod import;
+pub mod migration;
+pub mod qr;
+pub mod store;
+pub mod types;
+
+use hotp::HOTP_STEP;
+use store::Vault;
+use types::{Account, AccountUpdate, AppState, Entry, OtpKind, StoreData, StoredAccount, TotpStoreStatus};
+
+const STORE_FILE: &str = "totp.vault";
+pub const DEFAULT_ICON: &str = "/icons/default.svg";
+
+fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(dir.join(STORE_FILE))
+}
+
+pub fn algorithm_name(algorithm: Algorithm) -> &'static str {
+    match algorithm {
+        Algorithm::SHA1 => "SHA1",
+        Algorithm::SHA256 => "SHA256",
+        Algorithm::SHA512 => "SHA512",
+        Algorithm::Steam => "STEAM",
+    }
+}
+
+pub fn parse_algorithm(name: &str) -> Algorithm {
+    match name {
+        "SHA256" => Algorithm::SHA256,
+        "SHA512" => Algorithm::SHA512,
+        "STEAM" => Algorithm::Steam,
+        _ => Algorithm::SHA1,
+    }
+}
+
+/// Builds the generator for an RFC 6238/4226 account. Secrets shorter than
+/// the RFC's recommended 128 bits are accepted, since many services issue
+/// 80-bit ones.
+pub fn build_totp(algorithm: Algorithm, digits: usize, period: u64, secret: Vec<u8>) -> Result<TOTP, String> {
+    if !(6..=8).contains(&digits) {
+        return Err(format!("Unsupported number of digits: {}", digits));
+    }
+    if period == 0 {
+        return Err("Period must be greater than zero".to_string());
+    }
+    if secret.is_empty() {
+        return Err("Empty secret".to_string());
+    }
+    Ok(TOTP::new_unchecked(algorithm, digits, 1, period, secret))
+}
+
+impl Account {
+    /// An account with id `0` and the default icon; the id is assigned when
+    /// the account is added.
+    pub fn new(account: String, username: String, totp: TOTP, kind: OtpKind, counter: u64) -> Self {
+        Account {
+            id: 0,
+            icon: DEFAULT_ICON.to_string(),
+            account,
+            username,
+            totp,
+            kind,
+            counter,
+            tags: Vec::new(),
+            group: None,
+            pinned: false,
+        }
+    }
+}
+
+impl From<&Account> for StoredAccount {
+    fn from(account: &Account) -> Self {
+        StoredAccount {
+            id: account.id,
+            icon: account.icon.clone(),
+            account: account.account.clone(),
+            username: account.username.clone(),
+            secret: base32::encode(base32::Alphabet::RFC4648 { padding: false }, &account.totp.secret),
+            algorithm: algorithm_name(account.totp.algorithm).to_string(),
+            digits: account.totp.digits,
+            period: account.totp.step,
+            kind: account.kind,
+            counter: account.counter,
+            tags: account.tags.clone(),
+            group: account.group.clone(),
+            pinned: account.pinned,
+        }
+    }
+}
+
+impl TryFrom<StoredAccount> for Account {
+    type Error = String;
+
+    fn try_from(stored: StoredAccount) -> Result<Self, String> {
+        let secret = base32::decode(base32::Alphabet::RFC4648 { padding: false }, &stored.secret)
+            .ok_or_else(|| format!("Invalid secret stored for account {}", stored.id))?;
+        let totp = match parse_algorithm(&stored.algorithm) {
+            Algorithm::Steam => TOTP::new_steam(secret),
+            algorithm => build_totp(algorithm, stored.digits, stored.period, secret).map_err(|e| format!("Invalid account {}: {}", stored.id, e))?,
+        };
+        Ok(Account {
+            id: stored.id,
+            icon: icon_or_default(stored.icon),
+            account: stored.account,
+            username: stored.username,
+            totp,
+            kind: stored.kind,
+            counter: stored.counter,
+            tags: stored.tags,
+            group: stored.group,
+            pinned: stored.pinned,
+        })
+    }
+}
+
+/// Applies `change` to the accounts and saves the store. If saving fails the
+/// change is undone, so memory never holds accounts that are not on disk.
+pub fn modify<R>(state: &AppState, change: impl FnOnce(&mut Vec<Account>, &mut u32) -> Result<R, String>) -> Result<R, String> {
+    let vault = state.vault.lock().unwrap();
+    let vault = vault.as_ref().ok_or("The TOTP store is locked")?;
+    let mut accounts = state.accounts.lock().unwrap();
+    let mut next_id = state.next_id.lock().unwrap();
+
+    let previous = (accounts.clone(), *next_id);
+    let result = change(&mut accounts, &mut next_id)?;
+
+    let data = StoreData {
+        next_id: *next_id,
+        accounts: accounts.iter().map(StoredAccount::from).collect(),
+    };
+    if let Err(e) = vault.save(&data) {
+        (*accounts, *next_id) = previous;
+        return Err(e);
+    }
+    Ok(result)
+}
+
+/// Asks for the store secret again before revealing account secrets.
+pub fn confirm_secret(state: &AppState, secret: &str) -> Result<(), String> {
+    let vault = state.vault.lock().unwrap();
+    vault.as_ref().ok_or("The TOTP store is locked")?.verify(secret)
+}
+
+pub fn decode_secret(secret: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let secret = secret.trim().trim_end_matches('=').replace(' ', "").to_uppercase();
+    Ok(base32::decode(base32::Alphabet::RFC4648 { padding: false }, &secret).ok_or("Invalid base32 secret")?)
+}
+
+/// Steam Guard uses 5-character codes over its own alphabet, so it gets the
+/// Steam variant of `TOTP` regardless of any digits or algorithm given.
+pub fn steam_account(secret: &str, username: String) -> Result<Account, Box<dyn std::error::Error>> {
+    Ok(Account::new("Steam".to_string(), username, TOTP::new_steam(decode_secret(secret)?), OtpKind::Totp, 0))
+}
+
+/// Parses an `otpauth://totp/`, `otpauth://hotp/` or `steam://<secret>`
+/// URL into an account with id `0`; the caller assigns the id.
+pub fn parse_otpauth(url_str: &str) -> Result<Account, Box<dyn std::error::Error>> {
+    if let Some(secret) = url_str.trim().strip_prefix("steam://") {
+        return steam_account(secret.trim_matches('/'), String::new());
+    }
+
+    let url = Url::parse(url_str)?;
+    if url.scheme() != "otpauth" {
+        return Err("Not an otpauth:// URL".into());
+    }
+    let kind = match url.host_str() {
+        Some("totp") | Some("steam") => OtpKind::Totp,
+        Some("hotp") => OtpKind::Hotp,
+        _ => return Err("Only otpauth://totp/ and otpauth://hotp/ URLs are supported".into()),
+    };
+    let label = percent_decode_str(url.path().trim_start_matches('/')).decode_utf8_lossy().to_string();
+    let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+    let issuer = query.get("issuer").cloned().unwrap_or_default();
+    let secret_base32 = query.get("secret").ok_or("Missing secret")?;
+    if url.host_str() == Some("steam") || query.get("encoder").is_some_and(|encoder| encoder.eq_ignore_ascii_case("steam")) {
+        let username = label.split_once(':').map_or(label.as_str(), |(_, user)| user).to_string();
+        return steam_account(secret_base32, username);
+    }
+    let secret = decode_secret(secret_base32)?;
+    // Unsupported or malformed parameters are rejected rather than replaced
+    // with defaults, which would produce wrong codes.
+    let algorithm = match query.get("algorithm").map(|alg| alg.to_uppercase()) {
+        None => Algorithm::SHA1,
+        Some(alg) => match alg.as_str() {
+            "SHA1" => Algorithm::SHA1,
+            "SHA256" => Algorithm::SHA256,
+            "SHA512" => Algorithm::SHA512,
+            _ => return Err(format!("Unsupported algorithm: {}", alg).into()),
+        },
+    };
+    let digits = match query.get("digits") {
+        None => 6,
+        Some(d) => d.parse().map_err(|_| format!("Invalid digits: {}", d))?,
+    };
+    let period = match (kind, query.get("period")) {
+        (OtpKind::Hotp, _) => HOTP_STEP,
+        (_, None) => 30,
+        (_, Some(p)) => p.parse().map_err(|_| format!("Invalid period: {}", p))?,
+    };
+    let counter = match (kind, query.get("counter")) {
+        (OtpKind::Totp, _) => 0,
+        (OtpKind::Hotp, None) => return Err("Missing counter".into()),
+        (OtpKind::Hotp, Some(c)) => c.parse().map_err(|_| format!("Invalid counter: {}", c))?,
+    };
+    let totp = build_totp(algorithm, digits, period, secret)?;
+
+    let (account, username) = if let Some((acc, user)) = label.split_once(':') {
+        (acc.to_string(), user.to_string())
+    } else {
+        (issuer.clone(), label)
+    };
+
+    if !issuer.is_empty() && account != issuer {
+        return Err("Issuer mismatch between label and parameter".into());
+    }
+
+    Ok(Account::new(account, username, totp, kind, counter))
+}
+
+/// Serializes an account to the `otpauth://` URL [`parse_otpauth`] reads
+/// back. Steam accounts are written as `totp` with `digits=5&encoder=steam`,
+/// the form other authenticator apps understand.
+pub fn to_otpauth(account: &Account) -> String {
+    let host = match account.kind {
+        OtpKind::Totp => "totp",
+        OtpKind::Hotp => "hotp",
+    };
+    let label = if account.account.is_empty() {
+        account.username.clone()
+    } else {
+        format!("{}:{}", account.account, account.username)
+    };
+
+    let mut url = Url::parse(&format!("otpauth://{}/", host)).expect("valid otpauth base URL");
+    url.set_path(&label);
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("secret", &base32::encode(base32::Alphabet::RFC4648 { padding: false }, &account.totp.secret));
+        if !account.account.is_empty() {
+            query.append_pair("issuer", &account.account);
+        }
+        if account.totp.algorithm == Algorithm::Steam {
+            query.append_pair("digits", "5");
+            query.append_pair("encoder", "steam");
+        } else {
+            query.append_pair("algorithm", algorithm_name(account.totp.algorithm));
+            query.append_pair("digits", &account.totp.digits.to_string());
+            match account.kind {
+                OtpKind::Totp => query.append_pair("period", &account.totp.step.to_string()),
+                OtpKind::Hotp => query.append_pair("counter", &account.counter.to_string()),
+            };
+        }
+    }
+    url.to_string()
+}
+
+#[tauri::command]
+pub fn add_account(otpauth: String, state: State<AppState>) -> Result<(), String> {
+    let mut account = parse_otpauth(&otpauth).map_err(|e| e.to_string())?;
+    modify(&state, |accounts, next_id| {
+        account.id = *next_id;
+        *next_id += 1;
+        accounts.push(account);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn delete_account(id: u32, state: State<AppState>) -> Result<(), String> {
+    modify(&state, |accounts, _| {
+        let initial_len = accounts.len();
+        accounts.retain(|acc| acc.id != id);
+        if accounts.len() < initial_len {
+            Ok(())
+        } else {
+            Err("Account not found".to_string())
+        }
+    })
+}
+
+/// Icons are shown by the webview, so only bundled icons, `data:` URLs and
+/// HTTPS URLs are accepted.
+fn validate_icon(icon: &str) -> Result<(), String> {
+    if icon.starts_with("/icons/") || icon.starts_with("data:image/") || icon.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(format!("Unsupported icon: {}", icon))
+    }
+}
+
+/// Icons from stored accounts and backups are not trusted either; those that
+/// would be refused by `update_account` fall back to the default icon.
+pub(crate) fn icon_or_default(icon: String) -> String {
+    match validate_icon(&icon) {
+        Ok(()) => icon,
+        Err(_) => DEFAULT_ICON.to_string(),
+    }
+}
+
+/// Trims tags, drops empty ones and duplicates, keeping the given order.
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Renames an account, changes its icon, tags or group, or pins it.
+#[tauri::command]
+pub fn update_account(id: u32, update: AccountUpdate, state: State<AppState>) -> Result<(), String> {
+    let icon = match update.icon.map(|icon| icon.trim().to_string()) {
+        Some(icon) if icon.is_empty() => Some(DEFAULT_ICON.to_string()),
+        Some(icon) => {
+            validate_icon(&icon)?;
+            Some(icon)
+        }
+        None => None,
+    };
+
+    modify(&state, |accounts, _| {
+        let acc = accounts.iter_mut().find(|acc| acc.id == id).ok_or("Account not found")?;
+        if let Some(account) = update.account {
+            acc.account = account.trim().to_string();
+        }
+        if let Some(username) = update.username {
+            acc.username = username.trim().to_string();
+        }
+        if let Some(icon) = icon {
+            acc.icon = icon;
+        }
+        if let Some(tags) = update.tags {
+            acc.tags = normalize_tags(tags);
+        }
+        if let Some(group) = update.group {
+            let group = group.trim();
+            acc.group = (!group.is_empty()).then(|| group.to_string());
+        }
+        if let Some(pinned) = update.pinned {
+            acc.pinned = pinned;
+        }
+        Ok(())
+    })
+}
+
+fn reorder(accounts: &mut [Account], ids: &[u32]) -> Result<(), String> {
+    let positions: Vec<usize> = accounts.iter().enumerate().filter(|(_, acc)| ids.contains(&acc.id)).map(|(i, _)| i).collect();
+    if positions.len() != ids.len() {
+        return Err("Unknown or repeated account ids".to_string());
+    }
+
+    let reordered: Vec<Account> = ids.iter().filter_map(|id| accounts.iter().find(|acc| acc.id == *id).cloned()).collect();
+    for (position, account) in positions.into_iter().zip(reordered) {
+        accounts[position] = account;
+    }
+    Ok(())
+}
+
+/// Puts the accounts in `ids` in that order. The accounts keep the places
+/// they held among the others, so a filtered list can be reordered without
+/// moving the accounts that are hidden.
+#[tauri::command]
+pub fn reorder_accounts(ids: Vec<u32>, state: State<AppState>) -> Result<(), String> {
+    modify(&state, |accounts, _| reorder(accounts, &ids))
+}
+
+#[tauri::command]
+pub fn totp_store_status(app: AppHandle, state: State<AppState>) -> Result<TotpStoreStatus, String> {
+    Ok(TotpStoreStatus {
+        exists: store_path(&app)?.exists(),
+        unlocked: state.vault.lock().unwrap().is_some(),
+    })
+}
+
+/// Creates the encrypted account store. The secret is typed twice, since a
+/// mistyped one would lock the accounts away for good. The store is left
+/// locked, so the secret is proven once more by `unlock_totp_store`.
+#[tauri::command]
+pub fn create_totp_store(app: AppHandle, secret: String, confirmation: String) -> Result<(), String> {
+    let path = store_path(&app)?;
+    if path.exists() {
+        return Err("The account store already exists".to_string());
+    }
+    if secret != confirmation {
+        return Err("The secrets do not match".to_string());
+    }
+
+    Vault::create(&path, &secret)?.save(&StoreData::default())
+}
+
+/// Unlocks the encrypted account store with the user's secret and loads its
+/// accounts.
+#[tauri::command]
+pub fn unlock_totp_store(app: AppHandle, secret: String, state: State<AppState>) -> Result<(), String> {
+    let path = store_path(&app)?;
+    if !path.exists() {
+        return Err("No account store exists yet".to_string());
+    }
+    let (vault, data) = Vault::open(&path, &secret)?;
+
+    let accounts = data.accounts.into_iter().map(Account::try_from).collect::<Result<Vec<_>, _>>()?;
+
+    let mut vault_slot = state.vault.lock().unwrap();
+    *state.accounts.lock().unwrap() = accounts;
+    *state.next_id.lock().unwrap() = data.next_id;
+    *vault_slot = Some(vault);
+    Ok(())
+}
+
+/// Forgets the key and the decrypted accounts.
+#[tauri::command]
+pub fn lock_totp_store(state: State<AppState>) {
+    let mut vault = state.vault.lock().unwrap();
+    state.accounts.lock().unwrap().clear();
+    state.pending_imports.lock().unwrap().clear();
+    *state.next_id.lock().unwrap() = 0;
+    *vault = None;
+}
+
+fn entry(acc: &Account, now: u64) -> Entry {
+    if acc.kind == OtpKind::Hotp {
+        // HOTP codes are only revealed by `next_hotp_code`, which spends them.
+        return Entry {
+            id: acc.id,
+            icon: acc.icon.clone(),
+            account: acc.account.clone(),
+            username: acc.username.clone(),
+            code: String::new(),
+            algorithm: algorithm_name(acc.totp.algorithm).to_string(),
+            digits: acc.totp.digits,
+            period: 0,
+            seconds_remaining: 0,
+            next_code: String::new(),
+            kind: acc.kind,
+            counter: Some(acc.counter),
+            tags: acc.tags.clone(),
+            group: acc.group.clone(),
+            pinned: acc.pinned,
+        };
+    }
+
+    let step = acc.totp.step;
+    let seconds_remaining = step - now % step;
+    Entry {
+        id: acc.id,
+        icon: acc.icon.clone(),
+        account: acc.account.clone(),
+        username: acc.username.clone(),
+        code: acc.totp.generate(now),
+        algorithm: algorithm_name(acc.totp.algorithm).to_string(),
+        digits: acc.totp.digits,
+        period: step,
+        seconds_remaining,
+        next_code: acc.totp.generate(now + seconds_remaining),
+        kind: acc.kind,
+        counter: None,
+        tags: acc.tags.clone(),
+        group: acc.group.clone(),
+        pinned: acc.pinned,
+    }
+}
+
+/// Current and next codes of every account, or of those with `tag`, all
+/// computed for the same instant so `seconds_remaining` matches `code`.
+/// Pinned accounts come first; otherwise the order set by
+/// `reorder_accounts` is kept.
+#[tauri::command]
+pub fn get_accounts_with_codes(tag: Option<String>, state: State<AppState>) -> Vec<Entry> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    let accounts = state.accounts.lock().unwrap();
+    let mut entries: Vec<Entry> = accounts
+        .iter()
+        .filter(|acc| tag.as_ref().is_none_or(|tag| acc.tags.contains(tag)))
+        .map(|acc| entry(acc, now))
+        .collect();
+    entries.sort_by_key(|entry| !entry.pinned);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_percent_encoded_labels() {
+        let account = parse_otpauth("otpauth://totp/ACME%20Co:john.doe%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=ACME%20Co").unwrap();
+        assert_eq!(account.account, "ACME Co");
+        assert_eq!(account.username, "john.doe@example.com");
+        assert_eq!(account.kind, OtpKind::Totp);
+        assert_eq!(account.totp.digits, 6);
+        assert_eq!(account.totp.step, 30);
+    }
+
+    #[test]
+    fn accepts_80_bit_secrets() {
+        let account = parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP").unwrap();
+        assert_eq!(account.totp.secret.len(), 10);
+    }
+
+    #[test]
+    fn parses_hotp_counter() {
+        let account = parse_otpauth("otpauth://hotp/Example:alice?secret=JBSWY3DPEHPK3PXP&counter=42&digits=8").unwrap();
+        assert_eq!(account.kind, OtpKind::Hotp);
+        assert_eq!(account.counter, 42);
+        assert_eq!(account.totp.digits, 8);
+        assert!(parse_otpauth("otpauth://hotp/Example:alice?secret=JBSWY3DPEHPK3PXP").is_err());
+    }
+
+    #[test]
+    fn parses_steam_forms() {
+        for url in ["steam://JBSWY3DPEHPK3PXP", "otpauth://totp/Steam:alice?secret=JBSWY3DPEHPK3PXP&encoder=steam"] {
+            let account = parse_otpauth(url).unwrap();
+            assert_eq!(account.account, "Steam");
+            assert_eq!(account.totp.algorithm, Algorithm::Steam);
+            assert_eq!(account.totp.digits, 5);
+        }
+    }
+
+    #[test]
+    fn otpauth_round_trip() {
+        for url in [
+            "otpauth://totp/ACME%20Co:john.doe%40example.com?secret=JBSWY3DPEHPK3PXP&issuer=ACME%20Co&algorithm=SHA512&digits=8&period=60",
+            "otpauth://totp/Example:alice%20smith?secret=JBSWY3DPEHPK3PXP&issuer=Example",
+            "otpauth://hotp/Example:bob?secret=JBSWY3DPEHPK3PXP&counter=9",
+        ] {
+            let account = parse_otpauth(url).unwrap();
+            let again = parse_otpauth(&to_otpauth(&account)).unwrap();
+            assert_eq!((&again.account, &again.username), (&account.account, &account.username));
+            assert_eq!(again.kind, account.kind);
+            assert_eq!(again.counter, account.counter);
+            assert_eq!(again.totp, account.totp);
+        }
+    }
+
+    #[test]
+    fn steam_accounts_use_the_encoder_parameter() {
+        let account = parse_otpauth("steam://JBSWY3DPEHPK3PXP").unwrap();
+        let url = to_otpauth(&account);
+        assert!(url.starts_with("otpauth://totp/Steam:?"));
+        assert!(url.ends_with("&digits=5&encoder=steam"));
+
+        let again = parse_otpauth(&url).unwrap();
+        assert_eq!(again.totp, account.totp);
+        assert_eq!(again.account, "Steam");
+    }
+
+    fn numbered_accounts(count: u32) -> Vec<Account> {
+        (1..=count)
+            .map(|id| {
+                let mut account = parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP").unwrap();
+                account.id = id;
+                account
+            })
+            .collect()
+    }
+
+    fn ids(accounts: &[Account]) -> Vec<u32> {
+        accounts.iter().map(|acc| acc.id).collect()
+    }
+
+    #[test]
+    fn reorders_a_filtered_subset_in_place() {
+        let mut accounts = numbered_accounts(5);
+        // Accounts 1, 3 and 5 are shown; 2 and 4 are hidden by a filter.
+        reorder(&mut accounts, &[5, 1, 3]).unwrap();
+        assert_eq!(ids(&accounts), vec![5, 2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn reorder_rejects_unknown_or_repeated_ids() {
+        let mut accounts = numbered_accounts(3);
+        assert!(reorder(&mut accounts, &[1, 9]).is_err());
+        assert!(reorder(&mut accounts, &[2, 2]).is_err());
+        assert_eq!(ids(&accounts), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_unsupported_parameters() {
+        assert!(parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP&algorithm=MD5").is_err());
+        assert!(parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP&digits=4").is_err());
+        assert!(parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP&issuer=Other").is_err());
+    }
+
+    #[test]
+    fn stored_icons_are_validated() {
+        let mut account = parse_otpauth("otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP").unwrap();
+        for (icon, expected) in [("data:image/png;base64,AA==", "data:image/png;base64,AA=="), ("javascript:alert(1)", DEFAULT_ICON), ("http://example.com/a.png", DEFAULT_ICON)] {
+            account.icon = icon.to_string();
+            assert_eq!(Account::try_from(StoredAccount::from(&account)).unwrap().icon, expected);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, ImageReader, Luma};
+use qrcode::{render::svg, QrCode};
+use serde::Deserialize;
+use tauri::State;
+
+use super::{confirm_secret, to_otpauth, types::AppState};
+
+/// Schemes of the QR codes worth offering for import.
+const OTP_SCHEMES: [&str; 3] = ["otpauth://", "otpauth-migration://", "steam://"];
+
+/// Smallest side of rendered codes, large enough for phone cameras.
+const MIN_SIZE: u32 = 400;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+/// Finds every QR code in a PNG or JPEG image and returns the `otpauth://`,
+/// `otpauth-migration://` and `steam://` URLs they hold, in the order found.
+/// Nothing is added; the URLs are for the user to confirm and pass to
+/// `add_account` or `import_migration`.
+#[tauri::command]
+pub fn scan_qr_image(path: String) -> Result<Vec<String>, String> {
+    let reader = ImageReader::open(&path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    if !matches!(reader.format(), Some(ImageFormat::Png) | Some(ImageFormat::Jpeg)) {
+        return Err("Only PNG and JPEG images are supported".to_string());
+    }
+    let image = reader.decode().map_err(|e| format!("Failed to decode {}: {}", path, e))?;
+
+    let mut prepared = rqrr::PreparedImage::prepare(image.to_luma8());
+    let grids = prepared.detect_grids();
+    if grids.is_empty() {
+        return Err("No QR code found in the image".to_string());
+    }
+
+    let mut uris = Vec::new();
+    for grid in grids {
+        // A code that fails to decode, such as one cut off at the edge of a
+        // screenshot, should not hide the others.
+        match grid.decode() {
+            Ok((_, content)) => {
+                let content = content.trim().to_string();
+                if OTP_SCHEMES.iter().any(|scheme| content.starts_with(scheme)) && !uris.contains(&content) {
+                    uris.push(content);
+                }
+            }
+            Err(e) => eprintln!("Skipping unreadable QR code in {}: {}", path, e),
+        }
+    }
+
+    if uris.is_empty() {
+        return Err("The image has no authenticator QR codes".to_string());
+    }
+    Ok(uris)
+}
+
+/// Renders the `otpauth://` URL of an account as a QR code, to scan it into
+/// another authenticator. The code reveals the secret, so the store secret
+/// is asked for again.
+#[tauri::command]
+pub fn account_qr_code(id: u32, format: QrFormat, secret: String, state: State<AppState>) -> Result<Vec<u8>, String> {
+    confirm_secret(&state, &secret)?;
+    let uri = {
+        let accounts = state.accounts.lock().unwrap();
+        let account = accounts.iter().find(|acc| acc.id == id).ok_or("Account not found")?;
+        to_otpauth(account)
+    };
+
+    let code = QrCode::new(uri.as_bytes()).map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    match format {
+        QrFormat::Png => {
+            let image = code.render::<Luma<u8>>().min_dimensions(MIN_SIZE, MIN_SIZE).build();
+            let mut png = Cursor::new(Vec::new());
+            image.write_to(&mut png, ImageFormat::Png).map_err(|e| format!("Failed to encode PNG: {}", e))?;
+            Ok(png.into_inner())
+        }
+        QrFormat::Svg => Ok(code.render::<svg::Color>().min_dimensions(MIN_SIZE, MIN_SIZE).build().into_bytes()),
+    }
+}
@@ -0,0 +1,282 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+
+use super::types::StoreData;
+use crate::database::replace_file;
+
+const VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const MIN_SECRET_LEN: usize = 8;
+
+/// Argon2id parameters, stored with the file so they can be raised later
+/// without breaking existing stores.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+/// Upper bounds for parameters read from files this app did not write, such
+/// as imported backups: four times the defaults, so a crafted file cannot
+/// make the import allocate or compute without limit.
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 12;
+const MAX_PARALLELISM: u32 = 4;
+
+impl KdfParams {
+    fn within_limits(&self) -> bool {
+        self.memory_kib <= MAX_MEMORY_KIB && self.iterations <= MAX_ITERATIONS && self.parallelism <= MAX_PARALLELISM
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+/// On-disk layout: the KDF salt and parameters in the clear, and the
+/// accounts as XChaCha20-Poly1305 ciphertext under a fresh nonce per save.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The key of an unlocked store and where to write it.
+pub struct Vault {
+    path: PathBuf,
+    salt: Vec<u8>,
+    kdf: KdfParams,
+    key: Key,
+}
+
+fn derive_key(secret: &str, salt: &[u8], kdf: KdfParams) -> Result<Key, String> {
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32)).map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(secret.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>, String> {
+    STANDARD.decode(value).map_err(|e| format!("Invalid {} in TOTP store: {}", field, e))
+}
+
+impl Vault {
+    /// Sets up a new store protected by `secret`. Nothing is written until
+    /// the first [`Vault::save`].
+    pub fn create(path: &Path, secret: &str) -> Result<Self, String> {
+        if secret.chars().count() < MIN_SECRET_LEN {
+            return Err(format!("The secret must be at least {} characters long", MIN_SECRET_LEN));
+        }
+
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = KdfParams::default();
+
+        Ok(Vault {
+            path: path.to_path_buf(),
+            key: derive_key(secret, &salt, kdf)?,
+            salt,
+            kdf,
+        })
+    }
+
+    /// Opens an existing store. A wrong secret fails authentication of the
+    /// ciphertext, so it is reported the same way as a tampered file.
+    pub fn open(path: &Path, secret: &str) -> Result<(Self, StoreData), String> {
+        let contents = fs::read(path).map_err(|e| format!("Failed to read TOTP store: {}", e))?;
+        Self::unseal(path, &contents, secret, true)
+    }
+
+    /// Decrypts the contents of a store file that is not opened in place,
+    /// such as a backup being imported. Its key derivation parameters are
+    /// not trusted beyond the limits above.
+    pub fn read(contents: &[u8], secret: &str) -> Result<StoreData, String> {
+        Self::unseal(Path::new(""), contents, secret, false).map(|(_, data)| data)
+    }
+
+    fn unseal(path: &Path, contents: &[u8], secret: &str, trusted: bool) -> Result<(Self, StoreData), String> {
+        let file: VaultFile = serde_json::from_slice(contents).map_err(|e| format!("Failed to parse TOTP store: {}", e))?;
+        if file.version != VERSION {
+            return Err(format!("Unsupported TOTP store version: {}", file.version));
+        }
+        if !trusted && !file.kdf.within_limits() {
+            return Err("Key derivation parameters of the backup exceed the supported limits".to_string());
+        }
+
+        let salt = decode("salt", &file.salt)?;
+        let nonce = decode("nonce", &file.nonce)?;
+        if nonce.len() != 24 {
+            return Err("Invalid nonce in TOTP store".to_string());
+        }
+
+        let key = derive_key(secret, &salt, file.kdf)?;
+        let plaintext = XChaCha20Poly1305::new(&key)
+            .decrypt(XNonce::from_slice(&nonce), decode("ciphertext", &file.ciphertext)?.as_ref())
+            .map_err(|_| "Incorrect secret or corrupted TOTP store".to_string())?;
+        let data = serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse decrypted TOTP store: {}", e))?;
+
+        Ok((
+            Vault {
+                path: path.to_path_buf(),
+                salt,
+                kdf: file.kdf,
+                key,
+            },
+            data,
+        ))
+    }
+
+    /// Checks `secret` against the store's key, to confirm the user before
+    /// revealing secrets.
+    pub fn verify(&self, secret: &str) -> Result<(), String> {
+        if derive_key(secret, &self.salt, self.kdf)? != self.key {
+            return Err("Incorrect secret".to_string());
+        }
+        Ok(())
+    }
+
+    /// Encrypts `data` into the contents of a store file.
+    pub fn seal(&self, data: &StoreData) -> Result<Vec<u8>, String> {
+        let plaintext = serde_json::to_vec(data).map_err(|e| format!("Failed to serialize TOTP store: {}", e))?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|e| format!("Failed to encrypt TOTP store: {}", e))?;
+
+        let file = VaultFile {
+            version: VERSION,
+            salt: STANDARD.encode(&self.salt),
+            kdf: self.kdf,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        serde_json::to_vec_pretty(&file).map_err(|e| format!("Failed to serialize TOTP store: {}", e))
+    }
+
+    /// Encrypts `data` and atomically replaces the store with it.
+    pub fn save(&self, data: &StoreData) -> Result<(), String> {
+        let contents = self.seal(data)?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        replace_file(&self.path, |staged| staged.write_all(&contents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::totp::types::StoredAccount;
+
+    /// Cheap parameters so the tests don't spend seconds in Argon2.
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 1024,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn test_vault(path: &Path, secret: &str) -> Vault {
+        let salt = vec![7u8; SALT_LEN];
+        Vault {
+            path: path.to_path_buf(),
+            key: derive_key(secret, &salt, TEST_KDF).unwrap(),
+            salt,
+            kdf: TEST_KDF,
+        }
+    }
+
+    fn sample_data() -> StoreData {
+        StoreData {
+            next_id: 2,
+            accounts: vec![StoredAccount {
+                id: 1,
+                icon: "/icons/default.svg".to_string(),
+                account: "Example".to_string(),
+                username: "alice".to_string(),
+                secret: "JBSWY3DPEHPK3PXP".to_string(),
+                algorithm: "SHA1".to_string(),
+                digits: 6,
+                period: 30,
+                kind: Default::default(),
+                counter: 0,
+                tags: vec!["work".to_string()],
+                group: None,
+                pinned: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn rejects_short_secrets() {
+        assert!(Vault::create(Path::new("unused.vault"), "short").is_err());
+    }
+
+    #[test]
+    fn saves_and_reopens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("totp.vault");
+        test_vault(&path, "correct horse").save(&sample_data()).unwrap();
+
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("JBSWY3DP"));
+
+        let (vault, data) = Vault::open(&path, "correct horse").unwrap();
+        assert_eq!(data.next_id, 2);
+        assert_eq!(data.accounts[0].secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(data.accounts[0].tags, vec!["work"]);
+        assert!(vault.verify("correct horse").is_ok());
+        assert!(vault.verify("wrong horse").is_err());
+    }
+
+    #[test]
+    fn reads_sealed_backups() {
+        let vault = test_vault(Path::new("unused.vault"), "backup password");
+        let contents = vault.seal(&sample_data()).unwrap();
+        let data = Vault::read(&contents, "backup password").unwrap();
+        assert_eq!(data.accounts[0].username, "alice");
+    }
+
+    #[test]
+    fn rejects_excessive_kdf_parameters_in_backups() {
+        let contents = test_vault(Path::new("unused.vault"), "backup password").seal(&sample_data()).unwrap();
+        let mut file: serde_json::Value = serde_json::from_slice(&contents).unwrap();
+        file["kdf"]["memory_kib"] = (4 * 1024 * 1024).into();
+
+        let crafted = serde_json::to_vec(&file).unwrap();
+        let error = Vault::read(&crafted, "backup password").err().unwrap();
+        assert!(error.contains("limits"));
+    }
+
+    #[test]
+    fn wrong_secret_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("totp.vault");
+        test_vault(&path, "correct horse").save(&sample_data()).unwrap();
+
+        assert!(Vault::open(&path, "wrong horse").is_err());
+        assert!(Vault::read(&fs::read(&path).unwrap(), "wrong horse").is_err());
+    }
+}
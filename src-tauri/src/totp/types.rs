@@ -0,0 +1,121 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+use totp_rs::TOTP;
+
+use super::{backups::PendingImport, store::Vault};
+
+#[derive(Serialize)]
+pub struct Entry {
+    pub id: u32,
+    pub icon: String,
+    pub account: String,
+    pub username: String,
+    pub code: String,
+    pub algorithm: String,
+    pub digits: usize,
+    pub period: u64,
+    /// Seconds until `code` expires and `next_code` takes over.
+    pub seconds_remaining: u64,
+    pub next_code: String,
+    pub kind: OtpKind,
+    /// Counter of the next HOTP code; `None` for time-based accounts.
+    pub counter: Option<u64>,
+    pub tags: Vec<String>,
+    pub group: Option<String>,
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OtpKind {
+    #[default]
+    Totp,
+    Hotp,
+}
+
+#[derive(Clone)]
+pub struct Account {
+    pub id: u32,
+    pub icon: String,
+    pub account: String,  // issuer
+    pub username: String, // account_name
+    pub totp: TOTP,
+    pub kind: OtpKind,
+    /// Counter of the next HOTP code. Unused for time-based accounts.
+    pub counter: u64,
+    pub tags: Vec<String>,
+    /// The folder the account is listed under, if any.
+    pub group: Option<String>,
+    /// Pinned accounts are listed before the others.
+    pub pinned: bool,
+}
+
+/// The accounts of an unlocked store. `vault` is `None` while locked, and
+/// `accounts` is then empty.
+#[derive(Default)]
+pub struct AppState {
+    pub accounts: Mutex<Vec<Account>>,
+    pub next_id: Mutex<u32>,
+    pub vault: Mutex<Option<Vault>>,
+    /// Parsed backups awaiting `commit_import`, by import id.
+    pub pending_imports: Mutex<HashMap<String, PendingImport>>,
+}
+
+/// An account as written to the encrypted store. The secret is base32.
+#[derive(Serialize, Deserialize)]
+pub struct StoredAccount {
+    pub id: u32,
+    pub icon: String,
+    pub account: String,
+    pub username: String,
+    pub secret: String,
+    pub algorithm: String,
+    pub digits: usize,
+    pub period: u64,
+    #[serde(default)]
+    pub kind: OtpKind,
+    #[serde(default)]
+    pub counter: u64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// The decrypted contents of the store.
+#[derive(Serialize, Deserialize, Default)]
+pub struct StoreData {
+    pub next_id: u32,
+    pub accounts: Vec<StoredAccount>,
+}
+
+#[derive(Serialize)]
+pub struct TotpStoreStatus {
+    /// A store has been created, so unlocking asks for its secret instead
+    /// of setting a new one.
+    pub exists: bool,
+    pub unlocked: bool,
+}
+
+/// Changes made by `update_account`; fields left `None` are kept. An empty
+/// `group` removes the account from its group, and an empty `icon` restores
+/// the default icon.
+#[derive(Deserialize)]
+pub struct AccountUpdate {
+    pub account: Option<String>,
+    pub username: Option<String>,
+    pub icon: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub group: Option<String>,
+    pub pinned: Option<bool>,
+}
+
+/// A freshly issued HOTP code and the counter it was generated for.
+#[derive(Serialize)]
+pub struct HotpCode {
+    pub code: String,
+    pub counter: u64,
+}
@@ -0,0 +1,334 @@
+use rusqlite::{params_from_iter, types::Value, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    browsers::{ensure_browser_closed, locate_profile},
+    database::WorkingCopy,
+    logins::{delete_by_identity, DEPENDENT_TABLES},
+};
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    Copy,
+    Move,
+}
+
+/// What to do when the target profile already has a login with the same
+/// `signon_realm` and username.
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoginIdentity {
+    pub url: String,
+    pub username: String,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    Transferred,
+    Overwritten,
+    SkippedDuplicate,
+    /// `keep_both` was asked for, but the target already has a login equal
+    /// in every column Chromium requires to be unique.
+    DuplicateNotAllowed,
+    NotFound,
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct TransferOutcome {
+    #[serde(flatten)]
+    pub entry: LoginIdentity,
+    pub status: TransferStatus,
+    pub error: Option<String>,
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).map_err(|e| format!("Failed to prepare SQL statement: {}", e))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1)).map_err(|e| format!("Failed to read {} schema: {}", table, e))?;
+    names.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read {} schema: {}", table, e))
+}
+
+/// The columns of Chromium's `UNIQUE` constraint on `logins`.
+const UNIQUE_COLUMNS: [&str; 5] = ["origin_url", "username_element", "username_value", "password_element", "signon_realm"];
+
+/// Whether `target` already has a login equal to `row` in every unique
+/// column the two databases share.
+fn collides(target: &Connection, columns: &[String], row: &[Value]) -> Result<bool, String> {
+    let (conditions, values): (Vec<String>, Vec<&Value>) = columns
+        .iter()
+        .zip(row)
+        .filter(|(column, _)| UNIQUE_COLUMNS.contains(&column.as_str()))
+        .enumerate()
+        .map(|(i, (column, value))| (format!("\"{}\" IS ?{}", column, i + 1), value))
+        .unzip();
+    if conditions.is_empty() {
+        return Ok(false);
+    }
+
+    let sql = format!("SELECT COUNT(*) FROM logins WHERE {}", conditions.join(" AND "));
+    target
+        .query_row(&sql, params_from_iter(values), |row| row.get::<_, i64>(0))
+        .map(|count| count > 0)
+        .map_err(|e| format!("Failed to check for duplicates: {}", e))
+}
+
+/// Columns present in `table` in both databases, without the row id so the
+/// target assigns its own. Empty when either side lacks the table.
+fn shared_columns(source: &Connection, target: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let target_columns = table_columns(target, table)?;
+    Ok(table_columns(source, table)?.into_iter().filter(|c| c != "id" && target_columns.contains(c)).collect())
+}
+
+/// The columns copied for a login and for each of its dependent rows.
+struct TransferColumns {
+    logins: Vec<String>,
+    dependents: Vec<(&'static str, Vec<String>)>,
+}
+
+impl TransferColumns {
+    fn new(source: &Connection, target: &Connection) -> Result<Self, String> {
+        let mut dependents = Vec::new();
+        for table in DEPENDENT_TABLES {
+            let columns = shared_columns(source, target, table)?;
+            if columns.iter().any(|c| c == "parent_id") {
+                dependents.push((table, columns.into_iter().filter(|c| c != "parent_id").collect()));
+            }
+        }
+
+        Ok(TransferColumns {
+            logins: shared_columns(source, target, "logins")?,
+            dependents,
+        })
+    }
+}
+
+fn quoted(columns: &[String]) -> String {
+    columns.iter().map(|c| format!("\"{}\"", c)).collect::<Vec<_>>().join(", ")
+}
+
+fn placeholders(count: usize) -> String {
+    (1..=count).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ")
+}
+
+/// Copies the rows of `table` that belong to `source_id` and attaches them
+/// to `target_id`.
+fn copy_dependents(source: &Connection, target: &Connection, table: &str, columns: &[String], source_id: i64, target_id: i64) -> Result<(), String> {
+    let mut select = prepare(source, &format!("SELECT {} FROM {} WHERE parent_id = ?1", quoted(columns), table))?;
+    let rows = select
+        .query_map([source_id], |row| (0..columns.len()).map(|i| row.get::<_, Value>(i)).collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read {}: {}", table, e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+
+    let mut insert = prepare(
+        target,
+        &format!("INSERT INTO {} (parent_id, {}) VALUES ({})", table, quoted(columns), placeholders(columns.len() + 1)),
+    )?;
+    for row in rows {
+        let values = std::iter::once(Value::Integer(target_id)).chain(row);
+        insert.execute(params_from_iter(values)).map_err(|e| format!("Failed to insert into {}: {}", table, e))?;
+    }
+    Ok(())
+}
+
+fn prepare<'c>(conn: &'c Connection, sql: &str) -> Result<rusqlite::Statement<'c>, String> {
+    conn.prepare(sql).map_err(|e| format!("Failed to prepare SQL statement: {}", e))
+}
+
+fn transfer_entry(source: &Connection, target: &Connection, columns: &TransferColumns, entry: &LoginIdentity, on_duplicate: DuplicatePolicy) -> Result<TransferStatus, String> {
+    let column_list = quoted(&columns.logins);
+
+    let mut select = prepare(source, &format!("SELECT rowid, {} FROM logins WHERE signon_realm = ?1 AND username_value = ?2", column_list))?;
+    let rows = select
+        .query_map([&entry.url, &entry.username], |row| {
+            let values = (1..=columns.logins.len()).map(|i| row.get::<_, Value>(i)).collect::<Result<Vec<_>, _>>()?;
+            Ok((row.get::<_, i64>(0)?, values))
+        })
+        .map_err(|e| format!("Failed to read login: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read login: {}", e))?;
+
+    if rows.is_empty() {
+        return Ok(TransferStatus::NotFound);
+    }
+
+    let duplicates: i64 = target
+        .query_row("SELECT COUNT(*) FROM logins WHERE signon_realm = ?1 AND username_value = ?2", [&entry.url, &entry.username], |row| row.get(0))
+        .map_err(|e| format!("Failed to check for duplicates: {}", e))?;
+
+    let mut status = TransferStatus::Transferred;
+    if duplicates > 0 {
+        match on_duplicate {
+            DuplicatePolicy::Skip => return Ok(TransferStatus::SkippedDuplicate),
+            DuplicatePolicy::Overwrite => {
+                delete_by_identity(target, &entry.url, &entry.username)?;
+                status = TransferStatus::Overwritten;
+            }
+            DuplicatePolicy::KeepBoth => {
+                for (_, row) in &rows {
+                    if collides(target, &columns.logins, row)? {
+                        return Ok(TransferStatus::DuplicateNotAllowed);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut insert = prepare(target, &format!("INSERT INTO logins ({}) VALUES ({})", column_list, placeholders(columns.logins.len())))?;
+    for (source_id, row) in rows {
+        insert.execute(params_from_iter(row)).map_err(|e| format!("Failed to insert login: {}", e))?;
+        let target_id = target.last_insert_rowid();
+        for (table, dependent_columns) in &columns.dependents {
+            copy_dependents(source, target, table, dependent_columns, source_id, target_id)?;
+        }
+    }
+
+    Ok(status)
+}
+
+/// Copies or moves saved logins between two profiles of the same browser.
+/// Profiles of one install share the `Local State` encryption key, so rows
+/// are transplanted as-is, encrypted password included, together with their
+/// notes and insecure-credential flags.
+///
+/// The target is written back first; when moving, transferred entries are
+/// then removed from the source. Skipped and failed entries stay where they
+/// were. With `keep_both`, a login identical in every unique column to one
+/// already in the target is reported as `duplicate_not_allowed` and left
+/// where it was.
+#[tauri::command]
+pub fn transfer_account_entries(
+    browser_name: String,
+    source_profile: String,
+    target_profile: String,
+    entries: Vec<LoginIdentity>,
+    mode: TransferMode,
+    on_duplicate: DuplicatePolicy,
+) -> Result<Vec<TransferOutcome>, String> {
+    let source = locate_profile(&browser_name, &source_profile)?;
+    let target = locate_profile(&browser_name, &target_profile)?;
+    if source.login_data == target.login_data {
+        return Err(format!("Profiles '{}' and '{}' share the same Login Data", source.profile, target.profile));
+    }
+    ensure_browser_closed(&source.browser)?;
+
+    let source_copy = WorkingCopy::new(&source.login_data)?;
+    let target_copy = WorkingCopy::new(&target.login_data)?;
+
+    let mut outcomes = Vec::with_capacity(entries.len());
+    {
+        let source_conn = source_copy.open()?;
+        let mut target_conn = target_copy.open()?;
+        let columns = TransferColumns::new(&source_conn, &target_conn)?;
+
+        let mut tx = target_conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        for entry in entries {
+            // A savepoint per entry so a failed insert doesn't keep the
+            // deletion of the duplicate it was meant to overwrite.
+            let savepoint = tx.savepoint().map_err(|e| format!("Failed to create savepoint: {}", e))?;
+            let (status, error) = match transfer_entry(&source_conn, &savepoint, &columns, &entry, on_duplicate) {
+                Ok(status) => {
+                    savepoint.commit().map_err(|e| format!("Failed to release savepoint: {}", e))?;
+                    (status, None)
+                }
+                Err(e) => (TransferStatus::Failed, Some(e)),
+            };
+            outcomes.push(TransferOutcome { entry, status, error });
+        }
+        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    } // connections are dropped here, closing the file handles
+
+    // Overwriting deletes the target's duplicates, which must not survive in
+    // free pages.
+    if outcomes.iter().any(|o| o.status == TransferStatus::Overwritten) {
+        target_copy.write_back_deleted(&target.login_data)?;
+    } else {
+        target_copy.write_back(&target.login_data)?;
+    }
+
+    if mode == TransferMode::Move {
+        let transferred: Vec<&LoginIdentity> = outcomes
+            .iter()
+            .filter(|o| matches!(o.status, TransferStatus::Transferred | TransferStatus::Overwritten))
+            .map(|o| &o.entry)
+            .collect();
+
+        if !transferred.is_empty() {
+            {
+                let mut conn = source_copy.open()?;
+                let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+                for entry in transferred {
+                    delete_by_identity(&tx, &entry.url, &entry.username)?;
+                }
+                tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+            } // conn is dropped here, closing the file handle
+
+            source_copy
+                .write_back_deleted(&source.login_data)
+                .map_err(|e| format!("Entries were copied to '{}' but could not be removed from '{}': {}", target.profile, source.profile, e))?;
+        }
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "CREATE TABLE logins (id INTEGER PRIMARY KEY, signon_realm TEXT, username_value TEXT, password_value BLOB);
+         CREATE TABLE password_notes (id INTEGER PRIMARY KEY, parent_id INTEGER NOT NULL, key TEXT, value BLOB);
+         CREATE TABLE insecure_credentials (parent_id INTEGER, insecurity_type INTEGER NOT NULL);";
+
+    fn database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn
+    }
+
+    #[test]
+    fn dependent_rows_follow_the_login() {
+        let source = database();
+        source
+            .execute_batch(
+                "INSERT INTO logins VALUES (7, 'https://example.com/', 'alice', x'00');
+                 INSERT INTO password_notes VALUES (1, 7, '', x'6e6f7465');
+                 INSERT INTO insecure_credentials VALUES (7, 2);",
+            )
+            .unwrap();
+        let target = database();
+        target.execute_batch("INSERT INTO logins VALUES (1, 'https://other.com/', 'bob', x'00');").unwrap();
+
+        let entry = LoginIdentity {
+            url: "https://example.com/".to_string(),
+            username: "alice".to_string(),
+        };
+        let columns = TransferColumns::new(&source, &target).unwrap();
+        let status = transfer_entry(&source, &target, &columns, &entry, DuplicatePolicy::Skip).unwrap();
+        assert!(status == TransferStatus::Transferred);
+
+        let id: i64 = target.query_row("SELECT id FROM logins WHERE username_value = 'alice'", [], |row| row.get(0)).unwrap();
+        let note: Vec<u8> = target.query_row("SELECT value FROM password_notes WHERE parent_id = ?1", [id], |row| row.get(0)).unwrap();
+        let flags: i64 = target.query_row("SELECT COUNT(*) FROM insecure_credentials WHERE parent_id = ?1", [id], |row| row.get(0)).unwrap();
+        assert_eq!(note, b"note");
+        assert_eq!(flags, 1);
+
+        let status = transfer_entry(&source, &target, &columns, &entry, DuplicatePolicy::KeepBoth).unwrap();
+        assert!(status == TransferStatus::DuplicateNotAllowed);
+
+        delete_by_identity(&source, &entry.url, &entry.username).unwrap();
+        let orphans: i64 = source
+            .query_row("SELECT (SELECT COUNT(*) FROM password_notes) + (SELECT COUNT(*) FROM insecure_credentials)", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(orphans, 0);
+    }
+}
@@ -44,6 +44,7 @@ const translations: Translations = {
     "loading": "Loading...",
     "failed_to_load_passwords": "Failed to load passwords.",
     "no_browsers_found": "No browsers found.",
+    "profile_read_failed": "Passwords could not be read",
     "days_ago": "{days} days ago",
     "months_ago": "{months} months ago",
     "years_ago": "{years} years ago",
@@ -67,6 +68,55 @@ const translations: Translations = {
     "no_domain_breaches": "No domain breaches",
     "activate": "Activate",
     "license_key": "License Key",
+    "unlock_codes": "Unlock one-time codes",
+    "create_codes_secret": "Choose a secret to encrypt your one-time codes (at least 8 characters) and type it twice",
+    "confirm_codes_secret": "Repeat the secret",
+    "enter_codes_secret": "Enter the secret protecting your one-time codes",
+    "unlock": "Unlock",
+    "lock": "Lock",
+    "failed_to_unlock": "Failed to unlock: ",
+    "failed_to_create_store": "Failed to create the store: ",
+    "failed_to_migrate_accounts": "Some accounts could not be moved to the encrypted store: ",
+    "next_code": "Next",
+    "counter": "Counter",
+    "generate_code": "Generate code",
+    "resync": "Resynchronize",
+    "enter_first_code": "Enter a code the service accepts next",
+    "enter_second_code": "Enter the code that follows it",
+    "failed_to_generate_code": "Failed to generate code: ",
+    "failed_to_resync": "Failed to resynchronize: ",
+    "import_summary": "Imported {added} accounts, {duplicate} already present, {unsupported} unsupported.",
+    "missing_export_parts": "Parts of the export not scanned: {parts}",
+    "import_backup": "Import backup",
+    "backup_password": "Password for {file} (if encrypted)",
+    "failed_to_read_backup": "Failed to read backup: ",
+    "import_preview": "{count} accounts selected for import",
+    "import": "Import",
+    "already_present": "Already present",
+    "export_accounts": "Export accounts",
+    "export_all": "Export all accounts",
+    "export_matching": "Export the {count} matching accounts",
+    "app_backup": "Encrypted backup for this app",
+    "export_password": "Password for the backup",
+    "export_password_optional": "Password for the backup (optional)",
+    "exported_to": "Exported to ",
+    "failed_to_export": "Failed to export accounts: ",
+    "drop_qr_image": "Drop a PNG or JPEG image with QR codes to add its accounts",
+    "failed_to_scan_image": "Failed to read QR codes: ",
+    "qr_code": "QR code",
+    "qr_code_for": "QR code for {account} ({username}). Anyone who sees it can generate your codes.",
+    "failed_to_show_qr_code": "Failed to show the QR code: ",
+    "all_tags": "All tags",
+    "edit_account": "Edit account",
+    "save": "Save",
+    "icon_url": "Icon URL",
+    "tags": "Tags, separated by commas",
+    "group": "Group",
+    "pin": "Pin",
+    "unpin": "Unpin",
+    "move_up": "Move up",
+    "move_down": "Move down",
+    "failed_to_update_account": "Failed to update account: ",
   },
   ro: {
     "welcome_title": "Bun venit la TotLaUnLoc!",
@@ -101,6 +151,7 @@ const translations: Translations = {
     "loading": "Se încarcă...",
     "failed_to_load_passwords": "Nu s-au putut încărca parolele.",
     "no_browsers_found": "Nu s-au găsit browsere.",
+    "profile_read_failed": "Parolele nu au putut fi citite",
     "days_ago": "{days} zile în urmă",
     "months_ago": "{months} luni în urmă",
     "years_ago": "{years} ani în urmă",
@@ -124,6 +175,55 @@ const translations: Translations = {
     "no_domain_breaches": "Fără breșe de domeniu",
     "activate": "Activați",
     "license_key": "Cheie de licență",
+    "unlock_codes": "Deblocați codurile unice",
+    "create_codes_secret": "Alegeți un secret pentru criptarea codurilor unice (cel puțin 8 caractere) și introduceți-l de două ori",
+    "confirm_codes_secret": "Repetați secretul",
+    "enter_codes_secret": "Introduceți secretul care protejează codurile unice",
+    "unlock": "Deblocați",
+    "lock": "Blocați",
+    "failed_to_unlock": "Deblocarea a eșuat: ",
+    "failed_to_create_store": "Crearea depozitului a eșuat: ",
+    "failed_to_migrate_accounts": "Unele conturi nu au putut fi mutate în stocarea criptată: ",
+    "next_code": "Următorul",
+    "counter": "Contor",
+    "generate_code": "Generați codul",
+    "resync": "Resincronizați",
+    "enter_first_code": "Introduceți un cod pe care serviciul îl acceptă în continuare",
+    "enter_second_code": "Introduceți codul care urmează după el",
+    "failed_to_generate_code": "Generarea codului a eșuat: ",
+    "failed_to_resync": "Resincronizarea a eșuat: ",
+    "import_summary": "Au fost importate {added} conturi, {duplicate} existau deja, {unsupported} nu sunt suportate.",
+    "missing_export_parts": "Părți ale exportului nescanate: {parts}",
+    "import_backup": "Importă backup",
+    "backup_password": "Parola pentru {file} (dacă este criptat)",
+    "failed_to_read_backup": "Nu s-a putut citi backup-ul: ",
+    "import_preview": "{count} conturi selectate pentru import",
+    "import": "Importă",
+    "already_present": "Există deja",
+    "export_accounts": "Exportă conturile",
+    "export_all": "Exportă toate conturile",
+    "export_matching": "Exportă cele {count} conturi găsite",
+    "app_backup": "Backup criptat pentru această aplicație",
+    "export_password": "Parola pentru backup",
+    "export_password_optional": "Parola pentru backup (opțional)",
+    "exported_to": "Exportat în ",
+    "failed_to_export": "Nu s-au putut exporta conturile: ",
+    "drop_qr_image": "Trage aici o imagine PNG sau JPEG cu coduri QR pentru a adăuga conturile",
+    "failed_to_scan_image": "Nu s-au putut citi codurile QR: ",
+    "qr_code": "Cod QR",
+    "qr_code_for": "Codul QR pentru {account} ({username}). Oricine îl vede poate genera codurile tale.",
+    "failed_to_show_qr_code": "Nu s-a putut afișa codul QR: ",
+    "all_tags": "Toate etichetele",
+    "edit_account": "Editează contul",
+    "save": "Salvează",
+    "icon_url": "URL-ul iconiței",
+    "tags": "Etichete, separate prin virgulă",
+    "group": "Grup",
+    "pin": "Fixează",
+    "unpin": "Anulează fixarea",
+    "move_up": "Mută în sus",
+    "move_down": "Mută în jos",
+    "failed_to_update_account": "Nu s-a putut actualiza contul: ",
   },
 };
 